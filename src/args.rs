@@ -1,43 +1,106 @@
-use chrono::{DateTime, Datelike, TimeZone, Utc};
+use chrono::{DateTime, Datelike, Months, NaiveDate, TimeDelta, Utc};
+use chrono_tz::Tz;
 use clap::Parser;
 use serde::{Deserialize, Serialize};
 
-use crate::utils::end_of_month;
+use crate::{
+    errors::InvalidTimezone,
+    tablers::{Period, Rounding, RowOrder, TablerKind},
+    utils::{local_timezone, midnight},
+};
 
-fn start_month() -> DateTime<Utc> {
-    let utc = Utc::now();
-    Utc.with_ymd_and_hms(utc.year(), utc.month(), 1, 0, 0, 0)
-        .unwrap()
+fn max_hours() -> f64 {
+    10.0
 }
 
-fn end_month() -> DateTime<Utc> {
-    end_of_month(&start_month())
+fn refresh_days() -> u32 {
+    7
+}
+
+fn one_percent() -> u8 {
+    1
 }
 
 #[derive(Parser, Debug, Serialize, Deserialize, PartialEq, Clone)]
 #[command(version, about, long_about = None)]
 pub struct Args {
-    /// Provider used to retrieve entries
+    /// Provider used to retrieve entries, several comma separated providers are merged
     #[arg(short('P'), long)]
     pub provider: String,
 
     /// Options passed to the provider such as authentication informations and token
     /// ---
+    /// *Several Providers*
+    ///   provider.key: option only given to the provider, 'ics.path' for instance
+    ///   duplicates: keep-first (default), keep-longest or error when entries share an id or an interval and project
+    /// *Clockify, Toggl, Kimai, Harvest and Jira Options*
+    ///   max_attempts: attempts of a request when rate limited or unavailable, 3 by default
+    ///   backoff: milliseconds before the first retry, doubled after each attempt, 500 by default
+    ///   concurrency: maximum number of pages fetched at the same time when the API gives their number (Kimai, Harvest, Jira), 4 by default
     /// *Clockify Options*
     ///   token: Clockify authentication token
+    ///   base_url: Clockify API base URL, for regional or self-hosted instances
+    ///   workspace: id or name of the workspace, all to load every workspace
+    ///   user: comma separated ids, emails or names of the users, all for every user of the workspace, the authenticated user by default
+    ///   running: skip (default), clip or error for entries of running timers
+    /// *Toggl Options*
+    ///   token: Toggl Track API token
+    ///   base_url: Toggl Track API base URL
+    /// *Kimai Options*
+    ///   base_url: URL of the Kimai instance
+    ///   token: Kimai API token
+    ///   user: Kimai user name, only for legacy API passwords
+    ///   timezone: timezone of the Kimai user, --timezone or the system timezone by default
+    /// *Harvest Options*
+    ///   token: Harvest personal access token
+    ///   account: Harvest account id
+    ///   base_url: Harvest API base URL
+    ///   timezone: timezone of the started times, --timezone or the system timezone by default
+    /// *Jira Options*
+    ///   base_url: URL of the Jira instance
+    ///   token: Jira API token (Cloud) or personal access token (Server)
+    ///   email: email of the user, required by Jira Cloud
+    ///   jql: JQL filter restricting the issues, 'project = PRJ' for instance
+    ///   task: use the issue key (default) or summary as task
+    /// *File Options*
+    ///   path: JSON or CSV file containing the entries
+    ///   format: json or csv, deduced from the file extension by default
+    /// *Ics Options*
+    ///   path: iCalendar file containing the events
+    ///   pattern: template matched against the event summary, '{project} - {task}' for instance
+    ///   categories: use event categories as tags (default), project or task
+    ///   billable: whether entries are billable, true by default
+    ///   timezone: timezone of the floating times, --timezone or the system timezone by default
+    /// *Timewarrior and Watson Options*
+    ///   path: data folder (Timewarrior) or frames file (Watson), from the user configuration by default
+    ///   project: none, project, first-tag, second-tag or tag:N, first-tag (Timewarrior) or project (Watson) by default
+    ///   task: none, project, first-tag, second-tag or tag:N, none by default
+    ///   billable: whether entries are billable, true by default
+    /// *Git Options*
+    ///   repos: comma separated paths of the repositories
+    ///   author: author of the commits, user.email of the repository by default
+    ///   gap: maximum minutes between two commits of a session, 120 by default
+    ///   first: minutes of work before the first commit of a session, 30 by default
+    ///   project: repo (default) or branch
+    ///   billable: whether entries are billable, true by default
     #[arg(short, long)]
     #[serde(default)]
     pub provider_options: Vec<String>,
 
-    /// DateTime from wich to start retrieving entries
-    #[arg(short, long, default_value_t = start_month())]
-    #[serde(default = "start_month")]
-    pub start: DateTime<Utc>,
+    /// DateTime from wich to start retrieving entries, beginning of the current month by default
+    #[arg(short, long)]
+    #[serde(default)]
+    pub start: Option<DateTime<Utc>>,
+
+    /// DateTime until entries are retrieved, end of the current month by default
+    #[arg(short, long)]
+    #[serde(default)]
+    pub end: Option<DateTime<Utc>>,
 
-    /// DateTime until entries are retrieved
-    #[arg(short, long, default_value_t = end_month())]
-    #[serde(default = "end_month")]
-    pub end: DateTime<Utc>,
+    /// IANA timezone in which entries are grouped by day, 'Europe/Paris' for instance, the local timezone by default
+    #[arg(short('z'), long)]
+    #[serde(default)]
+    pub timezone: Option<String>,
 
     /// Include entries with "Ignore" tag
     #[arg(short, long, default_value_t = false)]
@@ -63,6 +126,117 @@ pub struct Args {
     #[arg(short, long, default_values_t = Vec::<String>::new())]
     #[serde(default)]
     pub display: Vec<String>,
+
+    /// Compute percents of the days or hours spent on projects and tasks
+    #[arg(short('t'), long, value_enum, default_value_t = TablerKind::Proportional)]
+    #[serde(default)]
+    pub tabler: TablerKind,
+
+    /// 'Project=60' or 'Project___Task=60' targets the percents of each month spent on a project or task, with the target tabler
+    #[arg(short('T'), long, default_values_t = Vec::<String>::new())]
+    #[serde(default)]
+    pub target: Vec<String>,
+
+    /// File containing one 'Project=60' target per line, lines starting with '#' are ignored
+    #[arg(long)]
+    #[serde(default)]
+    pub target_file: Option<String>,
+
+    /// Length of the columns: one column per day, per ISO week or per month
+    #[arg(long, value_enum, default_value_t = Period::Day)]
+    #[serde(default)]
+    pub period: Period,
+
+    /// Method giving the percents lost when rounding, so that each day sums to 100
+    #[arg(long, value_enum, default_value_t = Rounding::LargestRemainder)]
+    #[serde(default)]
+    pub rounding: Rounding,
+
+    /// Percents of a day allocated at once, 25 for quarter days for instance, the error is carried to the next days
+    #[arg(long, default_value_t = one_percent())]
+    #[serde(default = "one_percent")]
+    pub granularity: u8,
+
+    /// Hours of a full working day, days with less tracked time give a partial day
+    /// in the proportional tables, only with --period day and not with --tabler target
+    #[arg(long)]
+    #[serde(default)]
+    pub capacity: Option<f64>,
+
+    /// Row receiving the capacity left on partial days, so that every day is full
+    #[arg(long)]
+    #[serde(default)]
+    pub unassigned: Option<String>,
+
+    /// Public holidays, from an iCalendar (.ics) file of all-day events or a CSV file
+    /// with the date,country,name columns.
+    /// Holidays and days of leave last --capacity hours, use both so that a day off
+    /// weighs as much as a working day instead of 24 hours
+    #[arg(long)]
+    #[serde(default)]
+    pub holidays: Option<String>,
+
+    /// Country of the public holidays kept from the CSV file, every holiday by default
+    #[arg(long)]
+    #[serde(default)]
+    pub country: Option<String>,
+
+    /// Tag of the entries tracking leave, displayed in the leave row
+    #[arg(long)]
+    #[serde(default)]
+    pub leave_tag: Option<String>,
+
+    /// Days of leave, one day like 2024-10-14 or range like 2024-10-14..2024-10-18 per line.
+    /// With --per-user, 'user=path' gives the leave file of each user
+    #[arg(long)]
+    #[serde(default)]
+    pub leave_file: Vec<String>,
+
+    /// Hours tracked in a day above which the day is reported by the validation
+    #[arg(long, default_value_t = max_hours())]
+    #[serde(default = "max_hours")]
+    pub max_hours: f64,
+
+    /// Fail when the validation of the entries finds issues
+    #[arg(long, default_value_t = false)]
+    #[serde(default)]
+    pub strict: bool,
+
+    /// Compute one table per user, the team table contains the rows of every user prefixed by its name
+    #[arg(long, default_value_t = false)]
+    #[serde(default)]
+    pub per_user: bool,
+
+    /// Add the total of each row and of each column to the exported tables,
+    /// in days for the proportional tables
+    #[arg(long, default_value_t = false)]
+    #[serde(default)]
+    pub totals: bool,
+
+    /// Order of the rows of the exported tables
+    #[arg(long, value_enum, default_value_t = RowOrder::Alphabetical)]
+    #[serde(default)]
+    pub row_order: RowOrder,
+
+    /// Rows, or projects, displayed first by the custom row order
+    #[arg(long)]
+    #[serde(default)]
+    pub order: Vec<String>,
+
+    /// Folder where entries are cached, only days which may have changed are downloaded again
+    #[arg(long)]
+    #[serde(default)]
+    pub cache: Option<String>,
+
+    /// Only use the cached entries, without downloading them
+    #[arg(long, default_value_t = false)]
+    #[serde(default)]
+    pub offline: bool,
+
+    /// Days before the last download which are downloaded again when using the cache
+    #[arg(long, default_value_t = refresh_days())]
+    #[serde(default = "refresh_days")]
+    pub refresh: u32,
 }
 
 impl Default for Args {
@@ -70,19 +244,74 @@ impl Default for Args {
         Self {
             provider: "clockify".into(),
             provider_options: Default::default(),
-            start: start_month(),
-            end: end_month(),
+            start: None,
+            end: None,
+            timezone: None,
             ignored: false,
             billable: false,
             ignore_list: Default::default(),
             rename: Default::default(),
             display: Default::default(),
+            tabler: TablerKind::Proportional,
+            target: Default::default(),
+            target_file: None,
+            period: Period::Day,
+            rounding: Rounding::LargestRemainder,
+            granularity: one_percent(),
+            capacity: None,
+            unassigned: None,
+            holidays: None,
+            country: None,
+            leave_tag: None,
+            leave_file: Vec::new(),
+            max_hours: max_hours(),
+            strict: false,
+            per_user: false,
+            totals: false,
+            row_order: RowOrder::Alphabetical,
+            order: Default::default(),
+            cache: None,
+            offline: false,
+            refresh: refresh_days(),
         }
     }
 }
 
+impl Args {
+    /// Timezone in which entries are grouped by day
+    pub fn timezone(&self) -> Result<Tz, InvalidTimezone> {
+        match &self.timezone {
+            Some(name) => name
+                .parse()
+                .map_err(|_| InvalidTimezone { name: name.clone() }),
+            None => Ok(local_timezone()),
+        }
+    }
+
+    /// First day of the current month in the timezone
+    fn month(&self) -> NaiveDate {
+        let tz = self.timezone().unwrap_or(Tz::UTC);
+        let today = Utc::now().with_timezone(&tz).date_naive();
+        today.with_day(1).unwrap()
+    }
+
+    pub fn start(&self) -> DateTime<Utc> {
+        self.start
+            .unwrap_or_else(|| midnight(&self.timezone().unwrap_or(Tz::UTC), self.month()))
+    }
+
+    pub fn end(&self) -> DateTime<Utc> {
+        self.end.unwrap_or_else(|| {
+            let next = self.month() + Months::new(1);
+            midnight(&self.timezone().unwrap_or(Tz::UTC), next) - TimeDelta::milliseconds(1)
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use chrono::Timelike;
+
     use super::*;
 
     #[test]
@@ -94,4 +323,29 @@ mod tests {
                 .expect("valid json representing Args")
         )
     }
+
+    #[test]
+    fn month_in_timezone() {
+        let args = Args {
+            timezone: Some(String::from("Pacific/Auckland")),
+            ..Default::default()
+        };
+        let tz = args.timezone().unwrap();
+        let start = args.start().with_timezone(&tz);
+        assert_eq!((start.day(), start.hour(), start.minute()), (1, 0, 0));
+        let end = (args.end() + TimeDelta::milliseconds(1)).with_timezone(&tz);
+        assert_eq!((end.day(), end.hour(), end.minute()), (1, 0, 0));
+        assert_eq!(end.date_naive(), start.date_naive() + Months::new(1));
+
+        let args = Args {
+            timezone: Some(String::from("Mars/Olympus_Mons")),
+            ..Default::default()
+        };
+        assert_eq!(
+            args.timezone(),
+            Err(InvalidTimezone {
+                name: String::from("Mars/Olympus_Mons")
+            })
+        );
+    }
 }
@@ -0,0 +1,427 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    error::Error,
+    fs::read_to_string,
+};
+
+use chrono::{DateTime, Datelike, NaiveDate, TimeDelta, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::Deserialize;
+
+use crate::{
+    args::Args,
+    entries::Entry,
+    errors::{InvalidCalendar, InvalidLeaveFile},
+    providers::ics,
+    utils::midnight,
+};
+
+/// Row of the public holidays
+pub const HOLIDAY_ROW: &str = "Holiday";
+/// Row of the days of leave
+pub const LEAVE_ROW: &str = "Leave";
+
+/// Reason why a day is not worked
+#[derive(Debug, PartialEq, Clone)]
+pub enum DayOff {
+    Weekend,
+    /// Public holiday, with its name
+    Holiday(String),
+    Leave,
+}
+
+/// Public holidays and days of leave within the start..end window
+pub struct Calendar {
+    timezone: Tz,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    /// Duration of a day off, the whole day when there is no capacity
+    capacity: Option<TimeDelta>,
+    holidays: BTreeMap<NaiveDate, String>,
+    /// Days of leave of each user, the empty user being everyone
+    leave: BTreeMap<String, BTreeSet<NaiveDate>>,
+    leave_tag: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct HolidayRecord {
+    date: NaiveDate,
+    country: String,
+    name: String,
+}
+
+impl Calendar {
+    pub fn build(args: &Args) -> Result<Calendar, Box<dyn Error>> {
+        let mut calendar = Calendar {
+            timezone: args.timezone()?,
+            start: args.start(),
+            end: args.end(),
+            capacity: args
+                .capacity
+                .map(|hours| TimeDelta::seconds((hours * 3600.0).round() as i64)),
+            holidays: BTreeMap::new(),
+            leave: BTreeMap::new(),
+            leave_tag: args.leave_tag.clone(),
+        };
+
+        if let Some(path) = &args.holidays {
+            let content = read_to_string(path)?;
+            match path.to_lowercase().ends_with(".ics") {
+                true => calendar.load_ics(&content)?,
+                false => calendar.load_csv(path, &content, args.country.as_deref())?,
+            }
+        }
+        for leave_file in &args.leave_file {
+            let (user, path) = match (leave_file.split_once('='), args.per_user) {
+                (Some((user, path)), true) => (user, path),
+                (None, false) => ("", leave_file.as_str()),
+                _ => {
+                    return Err(InvalidLeaveFile {
+                        leave_file: leave_file.clone(),
+                        per_user: args.per_user,
+                    }
+                    .into())
+                }
+            };
+            calendar.load_leave(user, path, &read_to_string(path)?)?;
+        }
+
+        Ok(calendar)
+    }
+
+    /// All-day events of an iCalendar file, recurring events being expanded
+    fn load_ics(&mut self, content: &str) -> Result<(), Box<dyn Error>> {
+        let margin = TimeDelta::days(1);
+        for event in ics::parse(content)? {
+            if event.cancelled || !event.all_day() {
+                continue;
+            }
+            let (start, end) = (self.start - margin, self.end + margin);
+            for (start, end) in event.occurrences_in(start, end, &self.timezone) {
+                let mut day = start.with_timezone(&self.timezone).date_naive();
+                let end = end.with_timezone(&self.timezone).date_naive();
+                while day < end {
+                    self.holidays.insert(day, event.summary.clone());
+                    day = day.succ_opt().unwrap();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// CSV file with the date,country,name columns, only the holidays of the country
+    /// are kept when it is given
+    fn load_csv(
+        &mut self,
+        path: &str,
+        content: &str,
+        country: Option<&str>,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(content.as_bytes());
+        for (i, record) in reader.deserialize::<HolidayRecord>().enumerate() {
+            let record = record.map_err(|e| InvalidCalendar {
+                path: path.to_string(),
+                line: i + 2,
+                reason: e.to_string(),
+            })?;
+            if country.is_none_or(|c| c.eq_ignore_ascii_case(&record.country)) {
+                self.holidays.insert(record.date, record.name);
+            }
+        }
+        Ok(())
+    }
+
+    /// One day like 2024-10-14 or range of days like 2024-10-14..2024-10-18 per line,
+    /// lines starting with # being ignored
+    fn load_leave(&mut self, user: &str, path: &str, content: &str) -> Result<(), InvalidCalendar> {
+        for (i, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |reason: String| InvalidCalendar {
+                path: path.to_string(),
+                line: i + 1,
+                reason,
+            };
+            let parse = |d: &str| {
+                NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d")
+                    .map_err(|e| error(format!("{d}: {e}")))
+            };
+            let (first, last) = match line.split_once("..") {
+                Some((first, last)) => (parse(first)?, parse(last)?),
+                None => (parse(line)?, parse(line)?),
+            };
+            if last < first {
+                return Err(error(format!("{line} ends before it starts")));
+            }
+            self.leave
+                .entry(user.to_string())
+                .or_default()
+                .extend(first.iter_days().take_while(|d| *d <= last));
+        }
+        Ok(())
+    }
+
+    /// Reason why the day is not worked by the user, if any, holidays taking precedence
+    /// over leave
+    pub fn day_off(&self, day: NaiveDate, user: &str) -> Option<DayOff> {
+        if let Some(name) = self.holidays.get(&day) {
+            return Some(DayOff::Holiday(name.clone()));
+        }
+        if is_weekend(day) {
+            return Some(DayOff::Weekend);
+        }
+        [user, ""]
+            .iter()
+            .any(|u| self.leave.get(*u).is_some_and(|l| l.contains(&day)))
+            .then_some(DayOff::Leave)
+    }
+
+    /// Users having their own leave file, who may have no entry
+    pub fn users(&self) -> impl Iterator<Item = &String> {
+        self.leave.keys().filter(|u| !u.is_empty())
+    }
+
+    /// Entries tagged as leave are moved to the leave row
+    pub fn tag_leave(&self, e: Entry) -> Entry {
+        match &self.leave_tag {
+            Some(tag) if e.tags.contains(tag) => Entry {
+                project: String::from(LEAVE_ROW),
+                task: String::new(),
+                ..e
+            },
+            _ => e,
+        }
+    }
+
+    /// Entries filling the holidays and days of leave falling on working days, for each
+    /// of the users. They last the capacity, or the whole day without capacity, which
+    /// makes them outweigh the time tracked on the same day.
+    pub fn entries<'a>(&self, users: impl IntoIterator<Item = &'a String>) -> Vec<Entry> {
+        let users: BTreeSet<&String> = users.into_iter().collect();
+        let first = self.start.with_timezone(&self.timezone).date_naive();
+        let last = self.end.with_timezone(&self.timezone).date_naive();
+        let mut entries = Vec::new();
+
+        for day in first.iter_days().take_while(|d| *d <= last) {
+            let start = midnight(&self.timezone, day);
+            let end = match self.capacity {
+                Some(capacity) => start + capacity,
+                None => midnight(&self.timezone, day.succ_opt().unwrap()),
+            };
+            for user in &users {
+                let (project, description) = match self.day_off(day, user) {
+                    Some(DayOff::Holiday(name)) if !is_weekend(day) => (HOLIDAY_ROW, name),
+                    Some(DayOff::Leave) => (LEAVE_ROW, String::new()),
+                    _ => continue,
+                };
+                entries.push(Entry {
+                    id: format!("{project}_{day}"),
+                    description,
+                    project: String::from(project),
+                    user: user.to_string(),
+                    start: start.max(self.start),
+                    end: end.min(self.end),
+                    ..Default::default()
+                });
+            }
+        }
+
+        entries.retain(|e| e.start < e.end);
+        entries
+    }
+}
+
+/// Saturdays and sundays
+pub fn is_weekend(day: NaiveDate) -> bool {
+    matches!(day.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn calendar() -> Calendar {
+        Calendar {
+            timezone: Tz::UTC,
+            start: Utc.with_ymd_and_hms(2024, 12, 1, 0, 0, 0).unwrap(),
+            end: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+            capacity: None,
+            holidays: BTreeMap::new(),
+            leave: BTreeMap::new(),
+            leave_tag: None,
+        }
+    }
+
+    fn date(m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, m, d).unwrap()
+    }
+
+    #[test]
+    fn csv_by_country() {
+        let mut c = calendar();
+        let content = "date,country,name\n2024-12-25,FR,Noël\n2024-12-26, DE, Stephanstag\n";
+        c.load_csv("holidays.csv", content, Some("fr")).unwrap();
+        assert_eq!(
+            c.day_off(date(12, 25), ""),
+            Some(DayOff::Holiday(String::from("Noël")))
+        );
+        assert_eq!(c.day_off(date(12, 26), ""), None);
+
+        c.load_csv("holidays.csv", content, None).unwrap();
+        assert!(c.day_off(date(12, 26), "").is_some());
+
+        let error = c
+            .load_csv("holidays.csv", "date,country,name\n2024-13-01,FR,X\n", None)
+            .err()
+            .unwrap();
+        let error = error.downcast_ref::<InvalidCalendar>().unwrap();
+        assert_eq!(error.line, 2);
+    }
+
+    #[test]
+    fn ics_all_day_events() {
+        let mut c = calendar();
+        let content = "BEGIN:VCALENDAR\r
+BEGIN:VEVENT\r
+UID:1\r
+SUMMARY:Christmas\r
+DTSTART;VALUE=DATE:20201225\r
+RRULE:FREQ=YEARLY\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:2\r
+SUMMARY:Meeting\r
+DTSTART:20241224T090000Z\r
+DTEND:20241224T100000Z\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        c.load_ics(content).unwrap();
+        assert_eq!(
+            c.day_off(date(12, 25), ""),
+            Some(DayOff::Holiday(String::from("Christmas")))
+        );
+        assert_eq!(c.day_off(date(12, 24), ""), None);
+        assert_eq!(c.holidays.len(), 1);
+    }
+
+    #[test]
+    fn ics_time_zone() {
+        let mut c = Calendar {
+            timezone: "America/New_York".parse().unwrap(),
+            ..calendar()
+        };
+        let content = "BEGIN:VCALENDAR\r
+BEGIN:VEVENT\r
+UID:1\r
+SUMMARY:Christmas\r
+DTSTART;VALUE=DATE:20241225\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        c.load_ics(content).unwrap();
+        assert_eq!(c.holidays.keys().collect::<Vec<_>>(), vec![&date(12, 25)]);
+    }
+
+    #[test]
+    fn leave_file_per_user() {
+        let args = |leave_file: &str, per_user: bool| Args {
+            leave_file: vec![leave_file.to_string()],
+            per_user,
+            ..Default::default()
+        };
+        let error = Calendar::build(&args("leave.txt", true)).err().unwrap();
+        assert!(error.downcast_ref::<InvalidLeaveFile>().is_some());
+        let error = Calendar::build(&args("alice=leave.txt", false))
+            .err()
+            .unwrap();
+        assert!(error.downcast_ref::<InvalidLeaveFile>().is_some());
+    }
+
+    #[test]
+    fn leave_file() {
+        let mut c = calendar();
+        c.load_leave(
+            "",
+            "leave.txt",
+            "# Winter\n2024-12-23..2024-12-27\n\n2024-12-30\n",
+        )
+        .unwrap();
+        c.load_leave("alice", "alice.txt", "2024-12-31").unwrap();
+        assert_eq!(c.leave[""].len(), 6);
+        assert_eq!(c.day_off(date(12, 27), "bob"), Some(DayOff::Leave));
+        assert_eq!(c.day_off(date(12, 28), ""), Some(DayOff::Weekend));
+        assert_eq!(c.day_off(date(12, 31), ""), None);
+        assert_eq!(c.day_off(date(12, 31), "alice"), Some(DayOff::Leave));
+        assert_eq!(c.users().collect::<Vec<_>>(), vec!["alice"]);
+
+        let error = c
+            .load_leave("", "leave.txt", "2024-12-27..2024-12-23")
+            .err();
+        assert_eq!(error.unwrap().line, 1);
+        assert!(c.load_leave("", "leave.txt", "\n23/12/2024").is_err());
+    }
+
+    #[test]
+    fn tag_leave() {
+        let c = Calendar {
+            leave_tag: Some(String::from("vacation")),
+            ..calendar()
+        };
+        let e = Entry {
+            project: String::from("Project1"),
+            task: String::from("Task1"),
+            tags: vec![String::from("vacation")],
+            ..Default::default()
+        };
+        let e = c.tag_leave(e);
+        assert_eq!(e.to_project___task(), LEAVE_ROW);
+
+        let e = Entry {
+            project: String::from("Project1"),
+            ..Default::default()
+        };
+        assert_eq!(c.tag_leave(e).project, "Project1");
+    }
+
+    #[test]
+    fn entries() {
+        let mut c = Calendar {
+            capacity: Some(TimeDelta::hours(8)),
+            ..calendar()
+        };
+        c.holidays.insert(date(12, 25), String::from("Christmas"));
+        c.holidays.insert(date(12, 1), String::from("Sunday"));
+        c.leave.insert(
+            String::new(),
+            BTreeSet::from([date(12, 24), date(12, 25), date(12, 28)]),
+        );
+
+        let users = [String::from("alice"), String::from("bob")];
+        let entries = c.entries(&users);
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0].project, LEAVE_ROW);
+        assert_eq!(entries[0].user, "alice");
+        assert_eq!(entries[0].duration(), TimeDelta::hours(8));
+        assert_eq!(entries[2].project, HOLIDAY_ROW);
+        assert_eq!(entries[2].description, "Christmas");
+        assert_eq!(
+            entries[2].start,
+            Utc.with_ymd_and_hms(2024, 12, 25, 0, 0, 0).unwrap()
+        );
+
+        let c = Calendar {
+            capacity: None,
+            ..c
+        };
+        let entries = c.entries(&[String::new()]);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].duration(), TimeDelta::days(1));
+    }
+}
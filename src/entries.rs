@@ -1,15 +1,30 @@
 use std::fmt::Debug;
 
-use chrono::{DateTime, Datelike, TimeDelta, TimeZone, Utc};
+use chrono::{DateTime, NaiveTime, TimeDelta, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq, Default, Clone)]
+#[derive(Debug, PartialEq, Default, Clone, Serialize, Deserialize)]
 pub struct Entry {
+    #[serde(default)]
     pub id: String,
+    #[serde(default)]
     pub description: String,
+    #[serde(default)]
     pub billable: bool,
+    #[serde(default)]
     pub project: String,
+    #[serde(default)]
     pub task: String,
+    #[serde(default)]
     pub tags: Vec<String>,
+    /// User who tracked the entry, empty when the provider only loads the entries
+    /// of the authenticated user
+    #[serde(default)]
+    pub user: String,
+    /// Entry of a timer which was still running when loaded, clipped to that time
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub running: bool,
     pub end: DateTime<Utc>,
     pub start: DateTime<Utc>,
 }
@@ -27,21 +42,21 @@ impl Entry {
         }
     }
 
-    pub fn get_start_day(&self) -> DateTime<Utc> {
-        Utc.with_ymd_and_hms(
-            self.start.year(),
-            self.start.month(),
-            self.start.day(),
-            0,
-            0,
-            0,
-        )
-        .unwrap()
+    /// Day on which the entry starts in the timezone, as midnight UTC of this date so
+    /// that days of a table compare equal whatever the timezone
+    pub fn get_start_day(&self, tz: &Tz) -> DateTime<Utc> {
+        self.start
+            .with_timezone(tz)
+            .date_naive()
+            .and_time(NaiveTime::MIN)
+            .and_utc()
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use chrono::TimeZone;
+
     use super::*;
 
     #[test]
@@ -103,6 +118,18 @@ mod tests {
             ..Default::default()
         };
         let start = Utc.with_ymd_and_hms(2024, 10, 12, 0, 0, 0).unwrap();
-        assert_eq!(entry.get_start_day(), start)
+        assert_eq!(entry.get_start_day(&Tz::UTC), start)
+    }
+
+    #[test]
+    fn get_start_day_timezone() {
+        let entry = Entry {
+            start: Utc.with_ymd_and_hms(2024, 10, 12, 22, 30, 0).unwrap(),
+            ..Default::default()
+        };
+        let day = Utc.with_ymd_and_hms(2024, 10, 13, 0, 0, 0).unwrap();
+        assert_eq!(entry.get_start_day(&Tz::Europe__Paris), day);
+        let day = Utc.with_ymd_and_hms(2024, 10, 12, 0, 0, 0).unwrap();
+        assert_eq!(entry.get_start_day(&Tz::America__New_York), day);
     }
 }
@@ -13,3 +13,114 @@ impl Display for SplitError {
         write!(f, "Failed to split {} due to: {}", self.field, self.reason)
     }
 }
+
+#[derive(Error, Debug, PartialEq)]
+pub struct InvalidTimezone {
+    pub name: String,
+}
+
+impl Display for InvalidTimezone {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} is not an IANA timezone", self.name)
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub struct InvalidGranularity(pub u8);
+
+impl Display for InvalidGranularity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Granularity should divide 100 percents, not {}", self.0)
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub struct InvalidCapacity(pub f64);
+
+impl Display for InvalidCapacity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Capacity should be between 0 and 24 hours, not {}",
+            self.0
+        )
+    }
+}
+
+/// Option which cannot be combined with --capacity, the capacity being daily
+#[derive(Error, Debug, PartialEq)]
+pub struct CapacityConflict(pub String);
+
+impl Display for CapacityConflict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "--capacity is daily and cannot be used with {}", self.0)
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub struct InvalidCalendar {
+    pub path: String,
+    pub line: usize,
+    pub reason: String,
+}
+
+impl Display for InvalidCalendar {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Invalid calendar {} at line {}: {}",
+            self.path, self.line, self.reason
+        )
+    }
+}
+
+/// Leave file given for everyone while computing one table per user, or for a user
+/// while computing a single table
+#[derive(Error, Debug, PartialEq)]
+pub struct InvalidLeaveFile {
+    pub leave_file: String,
+    pub per_user: bool,
+}
+
+impl Display for InvalidLeaveFile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.per_user {
+            true => write!(
+                f,
+                "Leave file {} should be given as 'user=path' with --per-user",
+                self.leave_file
+            ),
+            false => write!(
+                f,
+                "Leave file {} of a user requires --per-user",
+                self.leave_file
+            ),
+        }
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub struct InvalidTimesheet {
+    pub issues: usize,
+}
+
+impl Display for InvalidTimesheet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} issues found in the timesheet", self.issues)
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub struct InvalidTarget {
+    pub target: String,
+}
+
+impl Display for InvalidTarget {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Target {} should be 'Project=percent' or 'Project___Task=percent'",
+            self.target
+        )
+    }
+}
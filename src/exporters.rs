@@ -2,19 +2,15 @@ pub mod console;
 pub mod csv;
 pub mod progessi;
 
-use std::{collections::HashMap, error::Error, fmt::Display};
+use std::{collections::HashMap, error::Error};
 
-use serde::Serialize;
+use crate::tablers::MyTable;
 
-use crate::tablers::Table;
-
-pub trait Exporter<'a> {
-    type Table: Table<Item<'a>: Display + Serialize>
-    where
-        Self: 'a;
+/// Export a table whose cells are of type `T`
+pub trait Exporter<T> {
     fn export(
         &mut self,
-        table: &Self::Table,
+        table: &MyTable<T>,
         display: &HashMap<String, String>,
     ) -> Result<(), Box<dyn Error>>;
 }
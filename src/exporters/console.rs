@@ -4,71 +4,95 @@ use std::{
     io::{self, Stdout, Write},
 };
 
-use chrono::{DateTime, Datelike, Utc};
+use chrono::{DateTime, Utc};
 
 use colored::{Color, Colorize};
 
-use crate::tablers::{MyTable, Table};
+use crate::tablers::{Cell, MyTable, Table};
 
 use super::Exporter;
 
 pub struct Console<W: Write> {
     writer: W,
+    totals: bool,
 }
 
 impl Console<Stdout> {
     pub fn stdout_output() -> Console<Stdout> {
         Console {
             writer: io::stdout(),
+            totals: false,
         }
     }
 }
 
-fn build_month_table(
+impl<W: Write> Console<W> {
+    /// Add a column with the total of each row and a row with the total of each column
+    pub fn with_totals(self, totals: bool) -> Console<W> {
+        Console { totals, ..self }
+    }
+}
+
+fn build_month_table<T: Cell>(
     month: &DateTime<Utc>,
     dates: &BTreeSet<DateTime<Utc>>,
-    t: &MyTable<u8>,
+    t: &MyTable<T>,
+    totals: bool,
 ) -> FormattedTable {
     let mut ptable = FormattedTable::new();
-    let ncol = dates.len() + 1;
+    let ncol = dates.len() + 2;
 
     let mut headers: Vec<String> = Vec::with_capacity(ncol);
-    headers.push(month.format("%Y %m").to_string());
+    headers.push(t.period().group_label(month));
     for d in dates {
-        headers.push(d.day().to_string());
+        headers.push(t.period().label(d));
+    }
+    if totals {
+        headers.push(String::from("Total"));
     }
     let headers = headers;
     ptable.set_header(headers);
 
-    let mut row_headers = t.row_headers().collect::<Vec<_>>();
-    row_headers.sort();
-    for r in row_headers {
+    for r in t.row_headers() {
         let mut row: Vec<String> = Vec::with_capacity(ncol);
         row.push(r.clone());
 
         for d in dates {
             row.push(t.get(r.clone(), *d).to_string());
         }
+        if totals {
+            row.push(t.row_total(r, dates).to_string());
+        }
+        ptable.add_row(row);
+    }
+
+    if totals {
+        let mut row: Vec<String> = Vec::with_capacity(ncol);
+        row.push(String::from("Total"));
+        for d in dates {
+            row.push(t.col_total(d).to_string());
+        }
+        row.push(t.total(dates).to_string());
         ptable.add_row(row);
     }
 
     ptable
 }
 
-impl<'a, W: Write + 'a> Exporter<'a> for Console<W> {
-    type Table = MyTable<u8>
-    where
-        Self: 'a;
-
+impl<W: Write, T: Cell> Exporter<T> for Console<W> {
     fn export(
         &mut self,
-        table: &Self::Table,
+        table: &MyTable<T>,
         _: &HashMap<String, String>,
     ) -> Result<(), Box<dyn Error>> {
-        let months = table.group_by_month();
+        let months = table.groups();
 
         for (k, v) in months.iter() {
-            writeln!(self.writer, "{}", build_month_table(&k, &v, table))?;
+            writeln!(
+                self.writer,
+                "{}",
+                build_month_table(&k, &v, table, self.totals)
+            )?;
         }
         Ok(())
     }
@@ -234,7 +258,10 @@ mod tests {
         let display = HashMap::<String, String>::new();
         let mut v = Vec::<u8>::new();
         let writer = Cursor::new(&mut v);
-        let mut csv = Console { writer };
+        let mut csv = Console {
+            writer,
+            totals: false,
+        };
 
         SHOULD_COLORIZE.set_override(true);
 
@@ -242,4 +269,25 @@ mod tests {
 
         assert_eq!(String::from_utf8_lossy(&v), String::from("\u{1b}[38;2;68;68;68m+---------+----+----+\n\u{1b}[0m\u{1b}[38;2;68;68;68m|\u{1b}[0m 2024 10 \u{1b}[38;2;68;68;68m|\u{1b}[0m 12 \u{1b}[38;2;68;68;68m|\u{1b}[0m 13 \u{1b}[38;2;68;68;68m|\u{1b}[0m\n\u{1b}[38;2;68;68;68m+---------+----+----+\n\u{1b}[0m\u{1b}[38;2;68;68;68m|\u{1b}[0m row1    \u{1b}[38;2;68;68;68m|\u{1b}[0m 8  \u{1b}[38;2;68;68;68m|\u{1b}[0m 8  \u{1b}[38;2;68;68;68m|\u{1b}[0m\n\u{1b}[38;2;68;68;68m+---------+----+----+\n\u{1b}[0m\u{1b}[38;2;68;68;68m|\u{1b}[0m row2    \u{1b}[38;2;68;68;68m|\u{1b}[0m 9  \u{1b}[38;2;68;68;68m|\u{1b}[0m 9  \u{1b}[38;2;68;68;68m|\u{1b}[0m\n\u{1b}[38;2;68;68;68m+---------+----+----+\n\u{1b}[0m\u{1b}[38;2;68;68;68m|\u{1b}[0m row3    \u{1b}[38;2;68;68;68m|\u{1b}[0m 10 \u{1b}[38;2;68;68;68m|\u{1b}[0m 10 \u{1b}[38;2;68;68;68m|\u{1b}[0m\n\u{1b}[38;2;68;68;68m+---------+----+----+\n\u{1b}[0m\n\u{1b}[38;2;68;68;68m+---------+----+\n\u{1b}[0m\u{1b}[38;2;68;68;68m|\u{1b}[0m 2024 11 \u{1b}[38;2;68;68;68m|\u{1b}[0m 13 \u{1b}[38;2;68;68;68m|\u{1b}[0m\n\u{1b}[38;2;68;68;68m+---------+----+\n\u{1b}[0m\u{1b}[38;2;68;68;68m|\u{1b}[0m row1    \u{1b}[38;2;68;68;68m|\u{1b}[0m 8  \u{1b}[38;2;68;68;68m|\u{1b}[0m\n\u{1b}[38;2;68;68;68m+---------+----+\n\u{1b}[0m\u{1b}[38;2;68;68;68m|\u{1b}[0m row2    \u{1b}[38;2;68;68;68m|\u{1b}[0m 9  \u{1b}[38;2;68;68;68m|\u{1b}[0m\n\u{1b}[38;2;68;68;68m+---------+----+\n\u{1b}[0m\u{1b}[38;2;68;68;68m|\u{1b}[0m row3    \u{1b}[38;2;68;68;68m|\u{1b}[0m 10 \u{1b}[38;2;68;68;68m|\u{1b}[0m\n\u{1b}[38;2;68;68;68m+---------+----+\n\u{1b}[0m\n"));
     }
+
+    #[test]
+    fn totals() {
+        let table = create_table();
+        let mut v = Vec::<u8>::new();
+        let writer = Cursor::new(&mut v);
+        let mut console = Console {
+            writer,
+            totals: false,
+        }
+        .with_totals(true);
+
+        SHOULD_COLORIZE.set_override(false);
+
+        console.export(&table, &HashMap::new()).unwrap();
+
+        let output = String::from_utf8_lossy(&v);
+        assert!(output.contains("| 2024 10 | 12        | 13        | Total     |"));
+        assert!(output.contains("| row3    | 10        | 10        | 0.20 days |"));
+        assert!(output.contains("| Total   | 0.27 days | 0.27 days | 0.54 days |"));
+    }
 }
@@ -3,48 +3,65 @@ use std::{collections::HashMap, error::Error, fs::create_dir_all};
 use chrono::Datelike;
 use csv::Writer;
 
-use crate::tablers::{MyTable, Table};
+use crate::tablers::{Cell, MyTable, Period, Table};
 
 use super::Exporter;
 
-pub struct CSV {}
-
-impl<'a> Exporter<'a> for CSV {
-    type Table = MyTable<u8>
-    where
-        Self: 'a;
+pub struct CSV {
+    /// Add a column with the total of each row and a row with the total of each column
+    pub totals: bool,
+}
 
+impl<T: Cell> Exporter<T> for CSV {
     fn export(
         &mut self,
-        table: &Self::Table,
+        table: &MyTable<T>,
         display: &HashMap<String, String>,
     ) -> Result<(), Box<dyn Error>> {
-        let months = table.group_by_month();
+        let months = table.groups();
+        let period = table.period();
 
         for (month, dates) in months.iter() {
-            let ncol = dates.len() + 1;
+            let ncol = dates.len() + 2;
             create_dir_all("export").ok();
-            let mut wtr =
-                Writer::from_path(format!("export/{}_{}.csv", month.year(), month.month()))
-                    .unwrap();
+            let path = match period {
+                Period::Day => format!("export/{}_{}.csv", month.year(), month.month()),
+                Period::Week => format!("export/{}_weeks.csv", month.year()),
+                Period::Month => format!("export/{}_months.csv", month.year()),
+            };
+            let mut wtr = Writer::from_path(path).unwrap();
 
             let mut headers: Vec<String> = Vec::with_capacity(ncol);
-            headers.push(month.format("%Y %m").to_string());
+            headers.push(period.group_label(month));
             for d in dates {
-                headers.push(d.day().to_string());
+                headers.push(period.label(d));
+            }
+            if self.totals {
+                headers.push(String::from("Total"));
             }
             let headers = headers;
             wtr.write_record(headers).unwrap();
 
-            let mut row_headers = table.row_headers().collect::<Vec<_>>();
-            row_headers.sort();
-            for r in row_headers {
+            for r in table.row_headers() {
                 let mut row: Vec<String> = Vec::with_capacity(ncol);
                 row.push(display.get(r).unwrap_or(r).clone());
 
                 for d in dates {
                     row.push(table.get(r.clone(), *d).to_string());
                 }
+                if self.totals {
+                    row.push(table.row_total(r, dates).to_string());
+                }
+                wtr.write_record(row).unwrap();
+            }
+
+            if self.totals {
+                let mut row: Vec<String> = Vec::with_capacity(ncol);
+                row.push(String::from("Total"));
+                for d in dates {
+                    row.push(table.col_total(d).to_string());
+                }
+                row.push(table.total(dates).to_string());
                 wtr.write_record(row).unwrap();
             }
             wtr.flush().unwrap();
@@ -120,7 +137,7 @@ mod tests {
     fn csv_no_display() {
         let table = create_table();
         let display = HashMap::<String, String>::new();
-        let mut csv = CSV {};
+        let mut csv = CSV { totals: false };
 
         let path = "export/2024_10.csv";
         remove_file(path).ok();
@@ -149,7 +166,7 @@ mod tests {
         let table = create_table();
         let mut display = HashMap::<String, String>::new();
         display.insert(String::from("row1"), String::from("displayed"));
-        let mut csv = CSV {};
+        let mut csv = CSV { totals: false };
 
         let path = "export/2024_10.csv";
         remove_file(path).ok();
@@ -171,4 +188,49 @@ mod tests {
             String::from("2024 11,13\ndisplayed,8\nrow2,9\nrow3,10\n")
         );
     }
+
+    #[test]
+    #[serial]
+    fn csv_totals() {
+        let table = create_table();
+        let mut csv = CSV { totals: true };
+
+        let path = "export/2024_10.csv";
+        remove_file(path).ok();
+        csv.export(&table, &HashMap::new()).unwrap();
+        let content = read_to_string(path).unwrap();
+        remove_file(path).ok();
+        remove_file("export/2024_11.csv").ok();
+        assert_eq!(
+            content,
+            String::from(
+                "2024 10,12,13,Total\nrow1,8,8,0.16 days\nrow2,9,9,0.18 days\n\
+                row3,10,10,0.20 days\nTotal,0.27 days,0.27 days,0.54 days\n"
+            )
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn csv_weeks() {
+        let mut table = MyTable::<u8>::default();
+        table.set_period(Period::Week);
+        for (m, d, v) in [(10, 14, 60), (10, 21, 70), (12, 30, 80)] {
+            table.insert(
+                String::from("row1"),
+                Utc.with_ymd_and_hms(2024, m, d, 0, 0, 0).unwrap(),
+                v,
+            );
+        }
+        let mut csv = CSV { totals: false };
+
+        let paths = ["export/2024_weeks.csv", "export/2025_weeks.csv"];
+        paths.iter().for_each(|p| drop(remove_file(p)));
+        csv.export(&table, &HashMap::new()).unwrap();
+        let content = paths.map(|p| read_to_string(p).unwrap());
+        paths.iter().for_each(|p| drop(remove_file(p)));
+        // The first ISO week of 2025 starts in 2024
+        assert_eq!(content[0], "2024,2024-W42,2024-W43\nrow1,60,70\n");
+        assert_eq!(content[1], "2025,2025-W01\nrow1,80\n");
+    }
 }
@@ -1,6 +1,6 @@
 use std::{collections::HashMap, error::Error, str::FromStr};
 
-use chrono::{DateTime, Datelike, NaiveDateTime, TimeZone, Utc};
+use chrono::{DateTime, Datelike, Months, NaiveDateTime, NaiveTime, TimeDelta, TimeZone, Utc};
 use gloo::events::EventListener;
 use wasm_bindgen::{
     convert::FromWasmAbi, describe::WasmDescribe, prelude::wasm_bindgen, JsCast, JsValue,
@@ -10,8 +10,8 @@ use wasm_bindgen::{
 use crate::{
     args::Args,
     provider_handle::ProviderHandle,
-    tablers::{MyTable, Table},
-    utils::end_of_month,
+    tablers::{Cell, MyTable, Table},
+    utils::midnight,
 };
 
 use std::sync::Arc;
@@ -211,14 +211,10 @@ fn add_timelines(document: &Document, timelines: &Vec<String>) {
     }
 }
 
-impl<'a> Exporter<'a> for Progessi {
-    type Table = MyTable<u8>
-    where
-        Self: 'a;
-
+impl Exporter<u8> for Progessi {
     fn export(
         &mut self,
-        table: &Self::Table,
+        table: &MyTable<u8>,
         display: &HashMap<String, String>,
     ) -> Result<(), Box<dyn Error>> {
         let timelines = get_timelines(&self.document);
@@ -298,10 +294,11 @@ impl Progessi {
 pub struct ProgessiPreview {
     start: DateTime<Utc>,
     document: Document,
+    totals: bool,
 }
 
 impl ProgessiPreview {
-    pub fn new(start: DateTime<Utc>, document: Document) -> ProgessiPreview {
+    pub fn new(start: DateTime<Utc>, document: Document, totals: bool) -> ProgessiPreview {
         let head = document.head().unwrap();
         let style = document
             .create_element("style")
@@ -331,7 +328,11 @@ impl ProgessiPreview {
         ));
         head.append_child(&style).unwrap();
 
-        ProgessiPreview { start, document }
+        ProgessiPreview {
+            start,
+            document,
+            totals,
+        }
     }
 }
 
@@ -358,14 +359,10 @@ fn create_row(document: &Document) -> HtmlElement {
     row.into()
 }
 
-impl<'a> Exporter<'a> for ProgessiPreview {
-    type Table = MyTable<u8>
-    where
-        Self: 'a;
-
+impl<T: Cell> Exporter<T> for ProgessiPreview {
     fn export(
         &mut self,
-        table: &Self::Table,
+        table: &MyTable<T>,
         display: &HashMap<String, String>,
     ) -> Result<(), Box<dyn Error>> {
         if let Some(element) = self
@@ -402,13 +399,16 @@ impl<'a> Exporter<'a> for ProgessiPreview {
         );
         row.append_child(&cell).unwrap();
 
-        let mut col_headers: Vec<DateTime<Utc>> = table.col_headers().cloned().collect();
-        col_headers.sort();
+        let col_headers: Vec<DateTime<Utc>> = table.col_headers().cloned().collect();
 
         for date in &col_headers {
             let cell = create_cell(&self.document, &date.day().to_string());
             row.append_child(&cell).unwrap();
         }
+        if self.totals {
+            let cell = create_cell(&self.document, "Total");
+            row.append_child(&cell).unwrap();
+        }
 
         let row_headers: Vec<String> = table.row_headers().cloned().collect();
 
@@ -428,6 +428,27 @@ impl<'a> Exporter<'a> for ProgessiPreview {
                 );
                 row.append_child(&span).unwrap();
             }
+            if self.totals {
+                let cell = create_cell(
+                    &self.document,
+                    &table.row_total(&r, &col_headers).to_string(),
+                );
+                row.append_child(&cell).unwrap();
+            }
+        }
+
+        if self.totals {
+            let row = create_row(&self.document);
+            preview.append_child(&row).unwrap();
+            let cell = create_cell(&self.document, "Total");
+            row.append_child(&cell).unwrap();
+
+            for date in &col_headers {
+                let cell = create_cell(&self.document, &table.col_total(date).to_string());
+                row.append_child(&cell).unwrap();
+            }
+            let cell = create_cell(&self.document, &table.total(&col_headers).to_string());
+            row.append_child(&cell).unwrap();
         }
 
         Ok(())
@@ -484,15 +505,22 @@ impl ProgessiHandle {
             .value()
             .replace(" ", "T");
 
-        let start = NaiveDateTime::from_str(&start)
+        // The month is loaded in the configured timezone while the tables use midnight UTC
+        // of the local dates
+        let month = NaiveDateTime::from_str(&start)
             .expect("date should be valid")
-            .and_local_timezone(Utc)
-            .unwrap();
-        let end = end_of_month(&start);
-
-        let args = Args { start, end, ..args };
+            .date();
+        let start = month.and_time(NaiveTime::MIN).and_utc();
+        let tz = args.timezone().unwrap_throw();
+
+        let args = Args {
+            start: Some(midnight(&tz, month)),
+            end: Some(midnight(&tz, month + Months::new(1)) - TimeDelta::milliseconds(1)),
+            ..args
+        };
 
-        let mut handle = ProviderHandle::new(args.clone()).expect("Provider not found");
+        let mut handle: ProviderHandle =
+            ProviderHandle::new(args.clone()).expect("Provider not found");
         handle.download_entries().await.unwrap_throw();
         handle.process().unwrap_throw();
 
@@ -516,7 +544,7 @@ impl ProgessiHandle {
         });
         on_click.forget();
 
-        let progessi = ProgessiPreview::new(start.clone(), document.clone());
+        let progessi = ProgessiPreview::new(start.clone(), document.clone(), args.totals);
         let clone = Arc::clone(&handle);
         let on_click = EventListener::new(&preview, "click", move |_event| {
             let handle = clone.lock().unwrap();
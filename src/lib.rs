@@ -1,4 +1,5 @@
 pub mod args;
+pub mod calendars;
 pub mod entries;
 pub mod errors;
 pub mod exporters;
@@ -6,5 +7,7 @@ pub mod filters;
 pub mod provider_handle;
 pub mod providers;
 pub mod renamers;
+pub mod splitters;
 pub mod tablers;
 pub mod utils;
+pub mod validators;
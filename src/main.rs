@@ -1,9 +1,16 @@
 use clap::Parser;
+use colored::Colorize;
 use std::error::Error;
 use time_tracking_manager::{
     args::Args,
+    errors::InvalidTimesheet,
     exporters::{console::Console, csv::CSV},
     provider_handle::ProviderHandle,
+    tablers::{
+        aggregate::Aggregate, hours::Hours, proportional::Proportional, target::Target, Tabler,
+        TablerKind,
+    },
+    validators::Severity,
 };
 
 #[tokio::main(flavor = "current_thread")]
@@ -11,12 +18,51 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let args = Args::parse();
     dbg!(&args);
 
-    let mut handle = ProviderHandle::new(args).expect("Provider should be available");
+    match args.tabler {
+        TablerKind::Proportional => run::<Aggregate<Proportional>>(args).await,
+        TablerKind::Hours => run::<Aggregate<Hours>>(args).await,
+        TablerKind::Target => run::<Aggregate<Target>>(args).await,
+    }
+}
+
+async fn run<T: Tabler>(args: Args) -> Result<(), Box<dyn Error>> {
+    let per_user = args.per_user;
+    let totals = args.totals;
+    let strict = args.strict;
+    let mut handle = ProviderHandle::<T>::new(args)?;
     handle.download_entries().await?;
+
+    let issues = handle.validate()?;
+    for issue in &issues {
+        match issue.severity {
+            Severity::Warning => println!("{}", issue.to_string().yellow()),
+            Severity::Error => println!("{}", issue.to_string().red()),
+        }
+    }
+    if strict && !issues.is_empty() {
+        return Err(InvalidTimesheet {
+            issues: issues.len(),
+        }
+        .into());
+    }
+
     handle.process()?;
 
-    handle.export(Box::new(Console::stdout_output())).unwrap();
-    handle.export(Box::new(CSV {})).unwrap();
+    if per_user {
+        for user in handle.users() {
+            println!("{user}");
+            handle.export_user(user, Box::new(Console::stdout_output().with_totals(totals)))?;
+        }
+    } else {
+        handle
+            .export(Box::new(Console::stdout_output().with_totals(totals)))
+            .unwrap();
+    }
+    handle.export(Box::new(CSV { totals })).unwrap();
+
+    if let Some(report) = handle.report() {
+        println!("{report}");
+    }
 
     Ok(())
 }
@@ -1,50 +1,84 @@
 use std::{
     cell::RefCell,
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap},
     error::Error,
     fmt::{Display, Formatter},
+    path::PathBuf,
 };
 
+use chrono::TimeDelta;
+
 use crate::{
     args::Args,
+    calendars::Calendar,
     entries::Entry,
     exporters::Exporter,
     filters::{predicate_filter, FilterParam},
-    providers::{clockify::Clockify, Provider},
+    providers::{
+        cache::Cached, clockify::Clockify, composite::Composite, file::File, git::Git,
+        harvest::Harvest, ics::Ics, jira::Jira, kimai::Kimai, timewarrior::Timewarrior,
+        toggl::Toggl, watson::Watson, Provider,
+    },
     renamers::Renames,
-    tablers::{proportional::Proportional, MyTable, Tabler},
+    splitters::{split_entry, SplitParam},
+    tablers::{proportional::Proportional, MyTable, Tabler, TablerParam},
     utils::{self, split_eq},
+    validators::{billable_ignored, validate, Issue, ValidateParam},
 };
 
-pub struct ProviderHandle {
+pub struct ProviderHandle<T: Tabler = Proportional> {
     provider: RefCell<Box<dyn Provider>>,
     args: Args,
     display: HashMap<String, String>,
-    table: MyTable<u8>,
+    table: MyTable<T::Cell>,
+    users: BTreeMap<String, MyTable<T::Cell>>,
+    report: Option<String>,
     entries: Vec<Entry>,
 }
 
-impl ProviderHandle {
-    pub fn new(args: Args) -> Result<ProviderHandle, ProviderNotFound> {
-        let options = args
+impl<T: Tabler> ProviderHandle<T> {
+    pub fn new(args: Args) -> Result<ProviderHandle<T>, Box<dyn Error>> {
+        TablerParam::build(&args)?;
+
+        let mut options: HashMap<String, String> = args
             .provider_options
-            .clone()
-            .into_iter()
-            .map(|o| split_eq(&o).unwrap())
-            .collect();
+            .iter()
+            .map(|o| split_eq(o))
+            .collect::<Result<_, _>>()?;
+        // Providers giving wall-clock times resolve them in the configured timezone
+        if let Some(tz) = &args.timezone {
+            options
+                .entry(String::from("timezone"))
+                .or_insert(tz.clone());
+        }
 
-        let provider = args.provider.clone();
+        let names: Vec<&str> = args.provider.split(',').map(|n| n.trim()).collect();
+        let provider = match names[..] {
+            [name] => build_provider(name, options.clone())?,
+            _ => {
+                let mut providers = Vec::with_capacity(names.len());
+                for name in names {
+                    providers.push(build_provider(name, Composite::options(name, &options))?);
+                }
+                Box::new(Composite::new(providers, &options)?)
+            }
+        };
 
-        match provider.as_str() {
-            "Clockify" | "clockify" => Ok(ProviderHandle::from_provider(
-                args,
-                Box::new(Clockify::new(options)),
+        let provider: Box<dyn Provider> = match &args.cache {
+            Some(dir) => Box::new(Cached::new(
+                provider,
+                PathBuf::from(dir).join(Cached::key(&args.provider, &options)),
+                TimeDelta::days(args.refresh.into()),
+                args.offline,
             )),
-            _ => Err(ProviderNotFound),
-        }
+            None if args.offline => return Err(CacheRequired.into()),
+            None => provider,
+        };
+
+        Ok(ProviderHandle::from_provider(args, provider))
     }
 
-    pub fn from_provider(args: Args, provider: Box<dyn Provider>) -> ProviderHandle {
+    pub fn from_provider(args: Args, provider: Box<dyn Provider>) -> ProviderHandle<T> {
         let mut display = HashMap::new();
 
         for d in args.display.iter() {
@@ -54,6 +88,8 @@ impl ProviderHandle {
 
         ProviderHandle {
             table: Default::default(),
+            users: Default::default(),
+            report: None,
             args,
             display,
             provider: RefCell::new(provider),
@@ -63,34 +99,128 @@ impl ProviderHandle {
 
     pub async fn download_entries(&mut self) -> Result<(), Box<dyn Error>> {
         let mut provider = self.provider.borrow_mut();
-        self.entries = provider.load(self.args.start, self.args.end).await?;
+        self.entries = provider.load(self.args.start(), self.args.end()).await?;
         Ok(())
     }
 
-    pub fn process(&mut self) -> Result<(), Box<dyn Error>> {
+    /// Filtered and renamed entries, the ones tagged as leave being moved to the leave row
+    fn prepare(&self, calendar: &Calendar) -> Result<Vec<Entry>, Box<dyn Error>> {
         let param = FilterParam::build(&self.args);
         let renames = Renames::build(&self.args)?;
-        let entries = self
+        Ok(self
             .entries
-            .clone()
-            .into_iter()
-            .filter(|x| predicate_filter(&x, &param))
+            .iter()
+            .filter(|x| predicate_filter(x, &param))
+            .cloned()
             .map(|x| renames.predicate_rename(x))
+            .map(|x| calendar.tag_leave(x))
+            .collect())
+    }
+
+    /// Check the downloaded entries before processing them
+    pub fn validate(&self) -> Result<Vec<Issue>, Box<dyn Error>> {
+        let param = ValidateParam::build(&self.args)?;
+        let mut issues = validate(&self.prepare(param.calendar())?, &param);
+        issues.extend(billable_ignored(&self.entries, &param));
+        issues.sort_by_key(|i| i.day);
+        Ok(issues)
+    }
+
+    pub fn process(&mut self) -> Result<(), Box<dyn Error>> {
+        let split = SplitParam::build(&self.args)?;
+        let tabler = TablerParam::build(&self.args)?;
+        let calendar = Calendar::build(&self.args)?;
+        let mut entries: Vec<Entry> = self
+            .prepare(&calendar)?
+            .into_iter()
+            .flat_map(|x| split_entry(x, &split))
             .collect();
 
-        self.table = Proportional::process(entries);
+        // Users with a leave file get their days off even without any entry
+        let users: BTreeSet<String> = match self.args.per_user {
+            true => entries
+                .iter()
+                .map(|e| &e.user)
+                .chain(calendar.users())
+                .cloned()
+                .collect(),
+            false => BTreeSet::from([String::new()]),
+        };
+        entries.extend(calendar.entries(&users));
+
+        if !self.args.per_user {
+            self.table = T::process(entries.clone(), &tabler);
+            self.table
+                .set_row_order(self.args.row_order, &self.args.order);
+            self.report = T::report(&entries, &self.table, &tabler);
+            return Ok(());
+        }
+
+        let mut users: BTreeMap<String, Vec<Entry>> = BTreeMap::new();
+        for e in entries {
+            users.entry(e.user.clone()).or_default().push(e);
+        }
+        let mut reports = Vec::new();
+        self.users = BTreeMap::new();
+        for (u, entries) in users {
+            let mut table = T::process(entries.clone(), &tabler);
+            table.set_row_order(self.args.row_order, &self.args.order);
+            if let Some(r) = T::report(&entries, &table, &tabler) {
+                reports.push(format!("{u}\n{r}"));
+            }
+            self.users.insert(u, table);
+        }
+        self.table = MyTable::merge_users(self.users.iter());
+        self.report = (!reports.is_empty()).then(|| reports.join("\n"));
+        Ok(())
+    }
+
+    /// Report of the tabler on the computed tables, if any
+    pub fn report(&self) -> Option<&str> {
+        self.report.as_deref()
+    }
+
+    /// Users having a table, only filled when computing one table per user
+    pub fn users(&self) -> impl Iterator<Item = &String> {
+        self.users.keys()
+    }
+
+    pub fn export(&self, mut exporter: Box<dyn Exporter<T::Cell>>) -> Result<(), Box<dyn Error>> {
+        exporter.export(&self.table, &self.display)?;
         Ok(())
     }
 
-    pub fn export(
+    /// Export the table of a single user
+    pub fn export_user(
         &self,
-        mut exporter: Box<dyn Exporter<Table = MyTable<u8>>>,
+        user: &str,
+        mut exporter: Box<dyn Exporter<T::Cell>>,
     ) -> Result<(), Box<dyn Error>> {
-        exporter.export(&self.table, &self.display)?;
+        let table = self.users.get(user).ok_or(UserNotFound(user.to_string()))?;
+        exporter.export(table, &self.display)?;
         Ok(())
     }
 }
 
+fn build_provider(
+    name: &str,
+    options: HashMap<String, String>,
+) -> Result<Box<dyn Provider>, Box<dyn Error>> {
+    match name {
+        "Clockify" | "clockify" => Ok(Box::new(Clockify::new(options)?)),
+        "Toggl" | "toggl" => Ok(Box::new(Toggl::new(options)?)),
+        "File" | "file" => Ok(Box::new(File::new(options)?)),
+        "Ics" | "ics" => Ok(Box::new(Ics::new(options)?)),
+        "Timewarrior" | "timewarrior" => Ok(Box::new(Timewarrior::new(options)?)),
+        "Watson" | "watson" => Ok(Box::new(Watson::new(options)?)),
+        "Kimai" | "kimai" => Ok(Box::new(Kimai::new(options)?)),
+        "Harvest" | "harvest" => Ok(Box::new(Harvest::new(options)?)),
+        "Jira" | "jira" => Ok(Box::new(Jira::new(options)?)),
+        "Git" | "git" => Ok(Box::new(Git::new(options)?)),
+        _ => Err(ProviderNotFound.into()),
+    }
+}
+
 #[derive(thiserror::Error, Debug, PartialEq)]
 pub struct ProviderNotFound;
 
@@ -100,12 +230,37 @@ impl Display for ProviderNotFound {
     }
 }
 
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub struct CacheRequired;
+
+impl Display for CacheRequired {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("The offline mode requires a cache folder.")
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub struct UserNotFound(pub String);
+
+impl Display for UserNotFound {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Could not find a table for the user {}.", self.0)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use async_trait::async_trait;
     use chrono::{DateTime, TimeDelta, TimeZone, Utc};
 
-    use crate::entries;
+    use crate::{
+        entries,
+        errors::{InvalidTimezone, SplitError},
+        tablers::{
+            hours::{DecimalHours, Hours},
+            Table,
+        },
+    };
 
     use super::*;
 
@@ -131,6 +286,8 @@ mod tests {
                     start: start
                         .checked_add_signed(TimeDelta::hours(day * 24))
                         .unwrap(),
+                    user: String::new(),
+                    running: false,
                     end: start
                         .checked_add_signed(TimeDelta::hours(day * 24 + 1))
                         .unwrap(),
@@ -145,6 +302,8 @@ mod tests {
                     start: start
                         .checked_add_signed(TimeDelta::hours(day * 24 + 1))
                         .unwrap(),
+                    user: String::new(),
+                    running: false,
                     end: start
                         .checked_add_signed(TimeDelta::hours(day * 24 + 2))
                         .unwrap(),
@@ -159,6 +318,8 @@ mod tests {
                     start: start
                         .checked_add_signed(TimeDelta::hours(day * 24 + 2))
                         .unwrap(),
+                    user: String::new(),
+                    running: false,
                     end: start
                         .checked_add_signed(TimeDelta::hours(day * 24 + 3))
                         .unwrap(),
@@ -173,14 +334,10 @@ mod tests {
     #[derive(Debug, Default)]
     struct TestExporter;
 
-    impl<'a> Exporter<'a> for TestExporter {
-        type Table = MyTable<u8>
-        where
-            Self: 'a;
-
+    impl<T> Exporter<T> for TestExporter {
         fn export(
             &mut self,
-            _: &Self::Table,
+            _: &MyTable<T>,
             _: &HashMap<String, String>,
         ) -> Result<(), Box<dyn Error>> {
             Ok(())
@@ -189,22 +346,31 @@ mod tests {
 
     #[test]
     fn not_found() {
-        let error = ProviderHandle::new(Args {
+        let error = ProviderHandle::<Proportional>::new(Args {
             provider: String::from("DoesNotExist"),
             ..Default::default()
         })
         .err()
         .unwrap();
-        assert_eq!(error, ProviderNotFound);
+        assert_eq!(error.downcast_ref(), Some(&ProviderNotFound));
+
+        let error = ProviderHandle::<Proportional>::new(Args {
+            provider: String::from("file,DoesNotExist"),
+            provider_options: vec![String::from("path=entries.json")],
+            ..Default::default()
+        })
+        .err()
+        .unwrap();
+        assert_eq!(error.downcast_ref(), Some(&ProviderNotFound));
     }
 
     #[tokio::test]
     async fn from_provider() -> Result<(), Box<dyn std::error::Error>> {
         let day = Utc.with_ymd_and_hms(2024, 10, 12, 0, 0, 0).unwrap();
-        let mut handle = ProviderHandle::from_provider(
+        let mut handle = ProviderHandle::<Proportional>::from_provider(
             Args {
-                start: day.checked_add_signed(TimeDelta::hours(12)).unwrap(),
-                end: day.checked_add_signed(TimeDelta::hours(37)).unwrap(),
+                start: Some(day.checked_add_signed(TimeDelta::hours(12)).unwrap()),
+                end: Some(day.checked_add_signed(TimeDelta::hours(37)).unwrap()),
                 ..Default::default()
             },
             Box::new(TestProvider {}),
@@ -215,4 +381,283 @@ mod tests {
         handle.export(exporter)?;
         Ok(())
     }
+
+    #[tokio::test]
+    async fn from_file() -> Result<(), Box<dyn std::error::Error>> {
+        let path = std::env::temp_dir().join("ttm_provider_handle.json");
+        std::fs::write(
+            &path,
+            r#"[
+                {"id": "1", "billable": true, "project": "Project1",
+                 "start": "2024-10-12T09:00:00Z", "end": "2024-10-12T12:00:00Z"},
+                {"id": "2", "billable": true, "project": "Project2",
+                 "start": "2024-10-12T13:00:00Z", "end": "2024-10-12T14:00:00Z"}
+            ]"#,
+        )?;
+
+        let day = Utc.with_ymd_and_hms(2024, 10, 12, 0, 0, 0).unwrap();
+        let mut handle = ProviderHandle::<Proportional>::new(Args {
+            provider: String::from("file"),
+            provider_options: vec![format!("path={}", path.to_string_lossy())],
+            start: Some(Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap()),
+            end: Some(Utc.with_ymd_and_hms(2024, 10, 31, 0, 0, 0).unwrap()),
+            timezone: Some(String::from("UTC")),
+            ..Default::default()
+        })
+        .unwrap();
+        handle.download_entries().await?;
+        handle.process()?;
+
+        assert_eq!(handle.table.get(String::from("Project1"), day), 75);
+        assert_eq!(handle.table.get(String::from("Project2"), day), 25);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn option_values() -> Result<(), Box<dyn std::error::Error>> {
+        // Values may contain =, like JQL queries or URLs
+        let path = std::env::temp_dir().join("ttm_provider_handle_a=b.json");
+        std::fs::write(
+            &path,
+            r#"[{"id": "1", "project": "Project1",
+                 "start": "2024-10-12T09:00:00Z", "end": "2024-10-12T12:00:00Z"}]"#,
+        )?;
+        let mut handle = ProviderHandle::<Proportional>::new(Args {
+            provider: String::from("file"),
+            provider_options: vec![format!("path={}", path.to_string_lossy())],
+            start: Some(Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap()),
+            end: Some(Utc.with_ymd_and_hms(2024, 10, 31, 0, 0, 0).unwrap()),
+            timezone: Some(String::from("UTC")),
+            ..Default::default()
+        })?;
+        handle.download_entries().await?;
+        assert_eq!(handle.entries.len(), 1);
+
+        let error = ProviderHandle::<Proportional>::new(Args {
+            provider: String::from("file"),
+            provider_options: vec![String::from("path")],
+            ..Default::default()
+        })
+        .err()
+        .unwrap();
+        assert!(error.downcast_ref::<SplitError>().is_some());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn hours() -> Result<(), Box<dyn std::error::Error>> {
+        let path = std::env::temp_dir().join("ttm_provider_handle_hours.json");
+        std::fs::write(
+            &path,
+            r#"[
+                {"id": "1", "billable": true, "project": "Project1",
+                 "start": "2024-10-12T09:00:00Z", "end": "2024-10-12T12:00:00Z"},
+                {"id": "2", "billable": true, "project": "Project2",
+                 "start": "2024-10-12T13:00:00Z", "end": "2024-10-12T14:30:00Z"}
+            ]"#,
+        )?;
+
+        let day = Utc.with_ymd_and_hms(2024, 10, 12, 0, 0, 0).unwrap();
+        let mut handle = ProviderHandle::<Hours>::new(Args {
+            provider: String::from("file"),
+            provider_options: vec![format!("path={}", path.to_string_lossy())],
+            start: Some(Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap()),
+            end: Some(Utc.with_ymd_and_hms(2024, 10, 31, 0, 0, 0).unwrap()),
+            timezone: Some(String::from("UTC")),
+            ..Default::default()
+        })
+        .unwrap();
+        handle.download_entries().await?;
+        handle.process()?;
+
+        assert_eq!(
+            handle.table.get(String::from("Project1"), day),
+            DecimalHours(TimeDelta::hours(3))
+        );
+        assert_eq!(
+            handle.table.get(String::from("Project2"), day).to_string(),
+            "1.50"
+        );
+        handle.export(Box::new(TestExporter))?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn holidays_and_leave() -> Result<(), Box<dyn std::error::Error>> {
+        let path = std::env::temp_dir().join("ttm_provider_handle_leave.json");
+        std::fs::write(
+            &path,
+            r#"[
+                {"id": "1", "billable": true, "project": "Project1",
+                 "start": "2024-10-14T09:00:00Z", "end": "2024-10-14T12:00:00Z"},
+                {"id": "2", "billable": true, "project": "Project1", "tags": ["off"],
+                 "start": "2024-10-16T09:00:00Z", "end": "2024-10-16T17:00:00Z"}
+            ]"#,
+        )?;
+        let holidays = std::env::temp_dir().join("ttm_provider_handle_holidays.csv");
+        std::fs::write(
+            &holidays,
+            "date,country,name\n2024-10-15,FR,Holiday\n2024-10-17,DE,Feiertag\n",
+        )?;
+
+        let mut handle = ProviderHandle::<Proportional>::new(Args {
+            provider: String::from("file"),
+            provider_options: vec![format!("path={}", path.to_string_lossy())],
+            start: Some(Utc.with_ymd_and_hms(2024, 10, 14, 0, 0, 0).unwrap()),
+            end: Some(Utc.with_ymd_and_hms(2024, 10, 21, 0, 0, 0).unwrap()),
+            timezone: Some(String::from("UTC")),
+            holidays: Some(holidays.to_string_lossy().to_string()),
+            country: Some(String::from("FR")),
+            leave_tag: Some(String::from("off")),
+            ..Default::default()
+        })
+        .unwrap();
+        handle.download_entries().await?;
+        handle.process()?;
+
+        let day = |d| Utc.with_ymd_and_hms(2024, 10, d, 0, 0, 0).unwrap();
+        assert_eq!(handle.table.get(String::from("Project1"), day(14)), 100);
+        assert_eq!(handle.table.get(String::from("Holiday"), day(15)), 100);
+        assert_eq!(handle.table.get(String::from("Leave"), day(16)), 100);
+        assert_eq!(handle.table.get(String::from("Holiday"), day(17)), 0);
+        assert_eq!(handle.table.col_headers().count(), 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn leave_per_user() -> Result<(), Box<dyn std::error::Error>> {
+        let path = std::env::temp_dir().join("ttm_provider_handle_leave_users.json");
+        std::fs::write(
+            &path,
+            r#"[
+                {"id": "1", "billable": true, "project": "Project1", "user": "alice",
+                 "start": "2024-10-14T09:00:00Z", "end": "2024-10-14T12:00:00Z"}
+            ]"#,
+        )?;
+        let leave = std::env::temp_dir().join("ttm_provider_handle_leave_bob.txt");
+        std::fs::write(&leave, "2024-10-14..2024-10-15\n")?;
+
+        let mut handle = ProviderHandle::<Proportional>::new(Args {
+            provider: String::from("file"),
+            provider_options: vec![format!("path={}", path.to_string_lossy())],
+            start: Some(Utc.with_ymd_and_hms(2024, 10, 14, 0, 0, 0).unwrap()),
+            end: Some(Utc.with_ymd_and_hms(2024, 10, 16, 0, 0, 0).unwrap()),
+            timezone: Some(String::from("UTC")),
+            leave_file: vec![format!("bob={}", leave.to_string_lossy())],
+            per_user: true,
+            ..Default::default()
+        })
+        .unwrap();
+        handle.download_entries().await?;
+        handle.process()?;
+
+        let day = |d| Utc.with_ymd_and_hms(2024, 10, d, 0, 0, 0).unwrap();
+        assert_eq!(handle.users().collect::<Vec<_>>(), vec!["alice", "bob"]);
+        assert_eq!(handle.users["bob"].get(String::from("Leave"), day(15)), 100);
+        assert_eq!(handle.users["alice"].get(String::from("Leave"), day(14)), 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn validation() -> Result<(), Box<dyn std::error::Error>> {
+        let path = std::env::temp_dir().join("ttm_provider_handle_validation.json");
+        std::fs::write(
+            &path,
+            r#"[
+                {"id": "1", "billable": true, "project": "Project1",
+                 "start": "2024-10-14T09:00:00Z", "end": "2024-10-14T17:00:00Z"},
+                {"id": "2", "billable": true, "project": "",
+                 "start": "2024-10-15T09:00:00Z", "end": "2024-10-15T17:00:00Z"},
+                {"id": "3", "billable": true, "project": "Project1", "tags": ["Ignore"],
+                 "start": "2024-10-15T12:00:00Z", "end": "2024-10-15T20:00:00Z"}
+            ]"#,
+        )?;
+
+        let mut handle = ProviderHandle::<Proportional>::new(Args {
+            provider: String::from("file"),
+            provider_options: vec![format!("path={}", path.to_string_lossy())],
+            start: Some(Utc.with_ymd_and_hms(2024, 10, 14, 0, 0, 0).unwrap()),
+            end: Some(Utc.with_ymd_and_hms(2024, 10, 17, 0, 0, 0).unwrap()),
+            timezone: Some(String::from("UTC")),
+            ..Default::default()
+        })
+        .unwrap();
+        handle.download_entries().await?;
+
+        let issues: Vec<String> = handle.validate()?.iter().map(|i| i.to_string()).collect();
+        assert_eq!(
+            issues,
+            vec![
+                "warning 2024-10-15: entry 2 has no project",
+                "error 2024-10-15: entry 3 is billable but tagged Ignore",
+                "warning 2024-10-16: no time tracked"
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn per_user() -> Result<(), Box<dyn std::error::Error>> {
+        let path = std::env::temp_dir().join("ttm_provider_handle_users.json");
+        std::fs::write(
+            &path,
+            r#"[
+                {"id": "1", "billable": true, "project": "Project1", "user": "Alice",
+                 "start": "2024-10-12T09:00:00Z", "end": "2024-10-12T12:00:00Z"},
+                {"id": "2", "billable": true, "project": "Project2", "user": "Alice",
+                 "start": "2024-10-12T13:00:00Z", "end": "2024-10-12T14:00:00Z"},
+                {"id": "3", "billable": true, "project": "Project2", "user": "Bob",
+                 "start": "2024-10-12T09:00:00Z", "end": "2024-10-12T17:00:00Z"}
+            ]"#,
+        )?;
+
+        let day = Utc.with_ymd_and_hms(2024, 10, 12, 0, 0, 0).unwrap();
+        let mut handle = ProviderHandle::<Proportional>::new(Args {
+            provider: String::from("file"),
+            provider_options: vec![format!("path={}", path.to_string_lossy())],
+            start: Some(Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap()),
+            end: Some(Utc.with_ymd_and_hms(2024, 10, 31, 0, 0, 0).unwrap()),
+            timezone: Some(String::from("UTC")),
+            per_user: true,
+            ..Default::default()
+        })
+        .unwrap();
+        handle.download_entries().await?;
+        handle.process()?;
+
+        assert_eq!(handle.users().collect::<Vec<_>>(), vec!["Alice", "Bob"]);
+        assert_eq!(handle.users["Alice"].get(String::from("Project1"), day), 75);
+        assert_eq!(handle.users["Bob"].get(String::from("Project2"), day), 100);
+        assert_eq!(handle.table.get(String::from("Alice: Project2"), day), 25);
+        assert_eq!(handle.table.get(String::from("Bob: Project2"), day), 100);
+        assert!(handle.export_user("Carol", Box::new(TestExporter)).is_err());
+        handle.export_user("Bob", Box::new(TestExporter))?;
+        Ok(())
+    }
+
+    #[test]
+    fn offline_requires_cache() {
+        let error = ProviderHandle::<Proportional>::new(Args {
+            provider: String::from("file"),
+            provider_options: vec![String::from("path=entries.json")],
+            offline: true,
+            ..Default::default()
+        })
+        .err()
+        .unwrap();
+        assert_eq!(error.downcast_ref(), Some(&CacheRequired));
+    }
+
+    #[test]
+    fn invalid_timezone() {
+        let error = ProviderHandle::<Proportional>::new(Args {
+            provider: String::from("file"),
+            provider_options: vec![String::from("path=entries.json")],
+            timezone: Some(String::from("Europe/Atlantis")),
+            ..Default::default()
+        })
+        .err()
+        .unwrap();
+        assert!(error.downcast_ref::<InvalidTimezone>().is_some());
+    }
 }
@@ -1,4 +1,5 @@
 use std::error::Error;
+use std::fmt::{Display, Formatter};
 
 use crate::entries::Entry;
 use async_trait::async_trait;
@@ -13,4 +14,70 @@ pub trait Provider {
     ) -> Result<Vec<Entry>, Box<dyn Error>>;
 }
 
+/// Provider option which is missing or cannot be used
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum OptionError {
+    Missing {
+        provider: String,
+        key: String,
+    },
+    Invalid {
+        provider: String,
+        key: String,
+        value: String,
+        /// Values which can be used
+        expected: String,
+    },
+}
+
+impl OptionError {
+    pub fn missing(provider: &str, key: &str) -> OptionError {
+        OptionError::Missing {
+            provider: provider.to_string(),
+            key: key.to_string(),
+        }
+    }
+
+    pub fn invalid(provider: &str, key: &str, value: &str, expected: &str) -> OptionError {
+        OptionError::Invalid {
+            provider: provider.to_string(),
+            key: key.to_string(),
+            value: value.to_string(),
+            expected: expected.to_string(),
+        }
+    }
+}
+
+impl Display for OptionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OptionError::Missing { provider, key } => {
+                write!(f, "{provider} provider options should contain {key}")
+            }
+            OptionError::Invalid {
+                provider,
+                key,
+                value,
+                expected,
+            } => write!(
+                f,
+                "{provider} provider option {key} should be {expected}, not {value}"
+            ),
+        }
+    }
+}
+
+pub mod cache;
 pub mod clockify;
+pub mod composite;
+pub mod file;
+pub mod git;
+pub mod harvest;
+pub mod ics;
+pub mod jira;
+pub mod kimai;
+pub mod mapping;
+pub mod rest;
+pub mod timewarrior;
+pub mod toggl;
+pub mod watson;
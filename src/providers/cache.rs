@@ -0,0 +1,398 @@
+use super::Provider;
+use crate::entries;
+use async_trait::async_trait;
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs::{create_dir_all, read_to_string, write};
+use std::path::PathBuf;
+
+type Interval = (DateTime<Utc>, DateTime<Utc>);
+
+/// Options left out of the cache key
+const SECRETS: [&str; 2] = ["token", "password"];
+
+/// Providers reading the `timezone` option, which is left out of the cache key of
+/// the others since it is given to every provider
+const TIMEZONED: [&str; 3] = ["harvest", "kimai", "ics"];
+
+/// Content of a cache file
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+struct Content {
+    /// Time of the last synchronization
+    watermark: Option<DateTime<Utc>>,
+    /// Intervals already loaded from the provider, sorted and disjoint
+    covered: Vec<Interval>,
+    entries: Vec<entries::Entry>,
+}
+
+/// Add an interval to sorted and disjoint intervals, merging the overlapping ones
+fn union(intervals: &mut Vec<Interval>, new: Interval) {
+    intervals.push(new);
+    intervals.sort();
+
+    let mut merged: Vec<Interval> = Vec::with_capacity(intervals.len());
+    for i in intervals.drain(..) {
+        match merged.last_mut() {
+            Some(last) if i.0 <= last.1 => last.1 = last.1.max(i.1),
+            _ => merged.push(i),
+        }
+    }
+    *intervals = merged;
+}
+
+/// Parts of `window` which are not covered by the sorted and disjoint intervals
+fn gaps(covered: &[Interval], window: Interval) -> Vec<Interval> {
+    let mut gaps = Vec::new();
+    let mut start = window.0;
+    for c in covered {
+        if c.1 <= start || c.0 >= window.1 {
+            continue;
+        }
+        if c.0 > start {
+            gaps.push((start, c.0));
+        }
+        start = c.1;
+    }
+    if start < window.1 {
+        gaps.push((start, window.1));
+    }
+    gaps
+}
+
+/// Interval which was never loaded while offline
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub struct NotCached {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl Display for NotCached {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Entries from {} to {} are not cached, load them without --offline first",
+            self.start, self.end
+        )
+    }
+}
+
+/// Persist the entries of a provider on disk so that only the days which may have
+/// changed are loaded again.
+///
+/// Days not loaded yet, and the last `refresh` days before the previous
+/// synchronization, are loaded from the provider. Offline, entries are only read
+/// from the cache, which should cover the whole interval.
+pub struct Cached {
+    provider: Box<dyn Provider>,
+    path: PathBuf,
+    refresh: TimeDelta,
+    offline: bool,
+}
+
+impl Cached {
+    pub fn new(
+        provider: Box<dyn Provider>,
+        path: PathBuf,
+        refresh: TimeDelta,
+        offline: bool,
+    ) -> Cached {
+        Cached {
+            provider,
+            path,
+            refresh,
+            offline,
+        }
+    }
+
+    /// Name of the cache file of a provider, depending on all its options except
+    /// the secrets, so that a token can be renewed without losing the cache, and the
+    /// timezone of the providers not reading it
+    pub fn key(provider: &str, options: &HashMap<String, String>) -> String {
+        let timezoned = |name: &str| TIMEZONED.contains(&name.trim().to_lowercase().as_str());
+        let options: BTreeMap<&String, &String> = options
+            .iter()
+            .filter(|(k, _)| {
+                let (prefix, key) = k.rsplit_once('.').unwrap_or(("", k));
+                match key {
+                    _ if SECRETS.contains(&key) => false,
+                    "timezone" if prefix.is_empty() => provider.split(',').any(timezoned),
+                    "timezone" => timezoned(prefix),
+                    _ => true,
+                }
+            })
+            .collect();
+
+        // FNV-1a, which unlike the hasher of the standard library is stable
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for (k, v) in options {
+            for b in k.bytes().chain([0]).chain(v.bytes()).chain([0]) {
+                hash = (hash ^ u64::from(b)).wrapping_mul(0x100000001b3);
+            }
+        }
+
+        let provider = provider
+            .chars()
+            .map(|c| match c.is_ascii_alphanumeric() {
+                true => c.to_ascii_lowercase(),
+                false => '-',
+            })
+            .collect::<String>();
+        format!("{provider}_{hash:016x}.json")
+    }
+
+    fn read(&self) -> Result<Content, Box<dyn Error>> {
+        match self.path.exists() {
+            true => Ok(serde_json::from_str(&read_to_string(&self.path)?)?),
+            false => Ok(Content::default()),
+        }
+    }
+
+    fn write(&self, content: &Content) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = self.path.parent() {
+            create_dir_all(parent)?;
+        }
+        write(&self.path, serde_json::to_string(content)?)?;
+        Ok(())
+    }
+
+    /// Intervals of the window to load from the provider
+    fn stale(&self, content: &Content, window: Interval) -> Vec<Interval> {
+        let mut stale = Vec::new();
+        for g in gaps(&content.covered, window) {
+            union(&mut stale, g);
+        }
+        if let Some(watermark) = content.watermark {
+            let from = (watermark - self.refresh).max(window.0);
+            if from < window.1 {
+                union(&mut stale, (from, window.1));
+            }
+        }
+        stale
+    }
+
+    async fn sync(
+        &mut self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<entries::Entry>, Box<dyn Error>> {
+        let mut content = self.read()?;
+
+        if !self.offline {
+            for (from, to) in self.stale(&content, (start, end)) {
+                let loaded = self.provider.load(from, to).await?;
+                // Entries of the window are replaced, as well as the entries overlapping
+                // it which were loaded again
+                let ids: HashSet<&String> = loaded.iter().map(|l| &l.id).collect();
+                content.entries.retain(|e| {
+                    let inside = e.start >= from && e.start < to;
+                    let reloaded = e.start < to && e.end > from && ids.contains(&e.id);
+                    !inside && !reloaded
+                });
+                content.entries.extend(loaded);
+                union(&mut content.covered, (from, to));
+            }
+            content.watermark = Some(now);
+            content.entries.sort_by_key(|e| e.start);
+            self.write(&content)?;
+        } else if let Some((from, to)) = gaps(&content.covered, (start, end)).first() {
+            return Err(NotCached {
+                start: *from,
+                end: *to,
+            }
+            .into());
+        }
+
+        Ok(content
+            .entries
+            .into_iter()
+            .filter(|e| e.end > start && e.start < end)
+            .collect())
+    }
+}
+
+#[async_trait(?Send)]
+impl Provider for Cached {
+    async fn load(
+        &mut self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<entries::Entry>, Box<dyn Error>> {
+        self.sync(start, end, Utc::now()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use std::{cell::RefCell, env, rc::Rc};
+
+    use super::*;
+
+    fn day(d: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 10, d, 0, 0, 0).unwrap()
+    }
+
+    /// Provider returning one entry per day and recording the loaded intervals
+    struct TestProvider {
+        loads: Rc<RefCell<Vec<Interval>>>,
+        description: String,
+    }
+
+    #[async_trait(?Send)]
+    impl Provider for TestProvider {
+        async fn load(
+            &mut self,
+            start: DateTime<Utc>,
+            end: DateTime<Utc>,
+        ) -> Result<Vec<entries::Entry>, Box<dyn Error>> {
+            self.loads.borrow_mut().push((start, end));
+            let mut entries = Vec::new();
+            let mut d = start;
+            while d < end {
+                entries.push(entries::Entry {
+                    id: d.format("%d").to_string(),
+                    description: self.description.clone(),
+                    start: d,
+                    end: d + TimeDelta::hours(1),
+                    ..Default::default()
+                });
+                d += TimeDelta::days(1);
+            }
+            Ok(entries)
+        }
+    }
+
+    fn cached(
+        name: &str,
+        description: &str,
+        offline: bool,
+    ) -> (Cached, Rc<RefCell<Vec<Interval>>>) {
+        let loads = Rc::new(RefCell::new(Vec::new()));
+        let provider = TestProvider {
+            loads: loads.clone(),
+            description: description.to_string(),
+        };
+        let path = env::temp_dir().join("ttm_cache").join(name);
+        (
+            Cached::new(Box::new(provider), path, TimeDelta::days(2), offline),
+            loads,
+        )
+    }
+
+    #[test]
+    fn intervals() {
+        let mut covered = vec![(day(1), day(3))];
+        union(&mut covered, (day(5), day(7)));
+        union(&mut covered, (day(2), day(4)));
+        assert_eq!(covered, vec![(day(1), day(4)), (day(5), day(7))]);
+        union(&mut covered, (day(4), day(5)));
+        assert_eq!(covered, vec![(day(1), day(7))]);
+
+        let covered = vec![(day(3), day(5)), (day(7), day(8))];
+        assert_eq!(
+            gaps(&covered, (day(1), day(10))),
+            vec![(day(1), day(3)), (day(5), day(7)), (day(8), day(10))]
+        );
+        assert!(gaps(&covered, (day(3), day(5))).is_empty());
+    }
+
+    #[test]
+    fn key() {
+        let options = HashMap::from([
+            (String::from("workspace"), String::from("My Workspace")),
+            (String::from("token"), String::from("secret")),
+        ]);
+        let key = Cached::key("Clockify", &options);
+        assert!(key.starts_with("clockify_"));
+        assert!(key.ends_with(".json"));
+
+        let mut other = options.clone();
+        other.insert(String::from("token"), String::from("renewed"));
+        assert_eq!(Cached::key("Clockify", &other), key);
+
+        other.insert(String::from("base_url"), String::from("http://localhost"));
+        assert_ne!(Cached::key("Clockify", &other), key);
+        assert_ne!(Cached::key("Toggl", &options), key);
+
+        // Only the providers reading the timezone depend on it
+        let mut paris = options.clone();
+        paris.insert(String::from("timezone"), String::from("Europe/Paris"));
+        assert_eq!(Cached::key("Clockify", &paris), key);
+        assert_ne!(
+            Cached::key("Harvest", &paris),
+            Cached::key("Harvest", &options)
+        );
+        assert_ne!(
+            Cached::key("toggl,ics", &paris),
+            Cached::key("toggl,ics", &options)
+        );
+
+        let composite = |path: &str| {
+            HashMap::from([
+                (String::from("ics.path"), path.to_string()),
+                (String::from("toggl.token"), String::from("secret")),
+            ])
+        };
+        assert_eq!(
+            Cached::key("toggl,ics", &composite("a.ics")),
+            Cached::key("toggl,ics", &composite("a.ics"))
+        );
+        assert_ne!(
+            Cached::key("toggl,ics", &composite("a.ics")),
+            Cached::key("toggl,ics", &composite("b.ics"))
+        );
+    }
+
+    #[tokio::test]
+    async fn same_id_outside_window() -> Result<(), Box<dyn Error>> {
+        let name = "outside.json";
+        std::fs::remove_file(env::temp_dir().join("ttm_cache").join(name)).ok();
+
+        // Ids are the day of the month, entries of september and october share them
+        let (mut cache, _) = cached(name, "first", false);
+        let september = Utc.with_ymd_and_hms(2024, 9, 1, 0, 0, 0).unwrap();
+        cache.sync(september, day(1), day(1)).await?;
+        let entries = cache.sync(september, day(11), day(11)).await?;
+        assert_eq!(entries.len(), 40);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn incremental_sync() -> Result<(), Box<dyn Error>> {
+        let name = "incremental.json";
+        std::fs::remove_file(env::temp_dir().join("ttm_cache").join(name)).ok();
+
+        let (mut cache, loads) = cached(name, "first", false);
+        let entries = cache.sync(day(1), day(11), day(11)).await?;
+        assert_eq!(entries.len(), 10);
+        assert_eq!(*loads.borrow(), vec![(day(1), day(11))]);
+
+        // Only the days after the watermark minus the refresh delay and the days
+        // never loaded are loaded again
+        let (mut cache, loads) = cached(name, "second", false);
+        let entries = cache.sync(day(5), day(15), day(15)).await?;
+        assert_eq!(*loads.borrow(), vec![(day(9), day(15))]);
+        assert_eq!(entries.len(), 10);
+        assert_eq!(entries[0].description, "first");
+        assert_eq!(entries[4].description, "second");
+
+        let (mut cache, loads) = cached(name, "third", true);
+        let entries = cache.sync(day(1), day(15), day(31)).await?;
+        assert!(loads.borrow().is_empty());
+        assert_eq!(entries.len(), 14);
+
+        let error = cache.sync(day(1), day(31), day(31)).await.unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<NotCached>(),
+            Some(&NotCached {
+                start: day(15),
+                end: day(31)
+            })
+        );
+        Ok(())
+    }
+}
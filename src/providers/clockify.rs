@@ -1,48 +1,167 @@
-use super::Provider;
+use super::rest::{Rest, RestOptions};
+use super::{OptionError, Provider};
 use crate::entries;
 use async_trait::async_trait;
 use chrono::DateTime;
 use chrono::Utc;
 use reqwest::header::HeaderMap;
 use reqwest::header::HeaderValue;
-use reqwest::Client;
+use reqwest::StatusCode;
 use serde::Deserialize;
-use serde_json;
 use std::collections::HashMap;
 use std::error::Error;
+use std::fmt::{Display, Formatter};
 
+const BASE_URL: &str = "https://api.clockify.me/api/v1";
+
+/// Load time entries from Clockify.
+///
+/// The workspace and the users can be chosen by id or name (email for users).
+/// Without workspace, the only workspace of the user is used; `all` loads every
+/// workspace. Entries of the authenticated user are loaded by default; when users
+/// are given, or `all` users of the workspace, entries are attributed to their user,
+/// whose email follows the name when several users have the same name.
+/// Running timers are skipped by default, they can also be clipped to the current
+/// time or make the loading fail.
+/// Clockify does not give the number of pages, so pages are fetched one after another
+/// until the `Last-Page` header or an empty page, whatever the `concurrency` option.
 #[derive(Debug)]
 pub struct Clockify {
+    base_url: String,
     token: String,
+    workspace: Option<String>,
+    users: Vec<String>,
+    running: Running,
+    rest: RestOptions,
+}
+
+/// What to do with the entry of a running timer
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Running {
+    Skip,
+    /// End the entry at the current time
+    Clip,
+    Error,
 }
 
 impl Clockify {
-    pub fn new(options: HashMap<String, String>) -> Clockify {
-        Clockify {
+    pub fn new(options: HashMap<String, String>) -> Result<Clockify, OptionError> {
+        Ok(Clockify {
+            base_url: options
+                .get("base_url")
+                .cloned()
+                .unwrap_or(String::from(BASE_URL)),
             token: options
                 .get("token")
-                .expect("Clockify provider options should contain a token")
+                .ok_or_else(|| OptionError::missing("Clockify", "token"))?
                 .clone(),
+            workspace: options.get("workspace").cloned(),
+            users: options
+                .get("user")
+                .map(|u| u.split(',').map(|u| u.trim().to_string()).collect())
+                .unwrap_or_default(),
+            running: match options.get("running").map(|r| r.as_str()) {
+                None | Some("skip") => Running::Skip,
+                Some("clip") => Running::Clip,
+                Some("error") => Running::Error,
+                Some(r) => {
+                    return Err(OptionError::invalid(
+                        "Clockify",
+                        "running",
+                        r,
+                        "skip, clip or error",
+                    ))
+                }
+            },
+            rest: RestOptions::new(&options).map_err(|e| e.of("Clockify"))?,
+        })
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum ClockifyError {
+    /// The token was rejected by Clockify
+    Unauthorized,
+    /// Too many requests were sent to Clockify
+    RateLimited,
+    /// An entry returned by Clockify could not be read
+    MalformedEntry {
+        id: String,
+        reason: String,
+    },
+    /// An entry has no end because its timer is still running
+    RunningTimer(String),
+    /// Several workspaces are available and none was chosen
+    SeveralWorkspaces(Vec<String>),
+    WorkspaceNotFound(String),
+    UserNotFound(String),
+}
+
+impl ClockifyError {
+    /// Give a Clockify meaning to the errors of requests
+    fn from_request(e: Box<dyn Error>) -> Box<dyn Error> {
+        match e.downcast_ref::<reqwest::Error>().and_then(|e| e.status()) {
+            Some(StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN) => {
+                ClockifyError::Unauthorized.into()
+            }
+            Some(StatusCode::TOO_MANY_REQUESTS) => ClockifyError::RateLimited.into(),
+            _ => e,
         }
     }
 }
 
-#[derive(Deserialize, Debug)]
+impl Display for ClockifyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClockifyError::Unauthorized => f.write_str("The Clockify token was rejected"),
+            ClockifyError::RateLimited => {
+                f.write_str("Too many requests were sent to Clockify, try again later")
+            }
+            ClockifyError::MalformedEntry { id, reason } => {
+                write!(f, "Clockify entry {id} is malformed: {reason}")
+            }
+            ClockifyError::RunningTimer(id) => write!(
+                f,
+                "Clockify entry {id} is still running, stop it or use the running option"
+            ),
+            ClockifyError::SeveralWorkspaces(w) => write!(
+                f,
+                "Several Clockify workspaces are available, choose one with the workspace option or use all: {}",
+                w.join(", ")
+            ),
+            ClockifyError::WorkspaceNotFound(w) => {
+                write!(f, "Could not find the Clockify workspace {w}")
+            }
+            ClockifyError::UserNotFound(u) => write!(f, "Could not find the Clockify user {u}"),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
 struct Workspace {
     id: String,
+    #[serde(default)]
+    name: String,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 struct User {
     id: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    email: String,
 }
 
 #[derive(Deserialize, Debug)]
 struct Entry {
     id: String,
+    #[serde(default)]
     description: String,
     billable: bool,
-    project: Project,
+    /// Null for the entries without project
+    #[serde(default)]
+    project: Option<Project>,
     #[serde(rename = "timeInterval")]
     time_interval: TimeInterval,
     #[serde(default)]
@@ -52,26 +171,58 @@ struct Entry {
 }
 
 impl Entry {
-    fn convert(&self) -> entries::Entry {
-        entries::Entry {
+    /// Return None for running timers when they are skipped.
+    /// `now` is the end given to the running timers which are clipped.
+    fn convert(
+        &self,
+        user: &str,
+        running: Running,
+        now: DateTime<Utc>,
+    ) -> Result<Option<entries::Entry>, ClockifyError> {
+        let parse = |s: &str| {
+            DateTime::parse_from_rfc3339(s)
+                .map(|d| d.to_utc())
+                .map_err(|e| ClockifyError::MalformedEntry {
+                    id: self.id.clone(),
+                    reason: e.to_string(),
+                })
+        };
+
+        let start = parse(&self.time_interval.start)?;
+        let clipped = self.time_interval.end.is_none();
+        let end = match (&self.time_interval.end, running) {
+            (Some(end), _) => parse(end)?,
+            (None, Running::Skip) => return Ok(None),
+            (None, Running::Clip) if now > start => now,
+            (None, Running::Clip) => return Ok(None),
+            (None, Running::Error) => return Err(ClockifyError::RunningTimer(self.id.clone())),
+        };
+
+        let (project, task) = match &self.project {
+            Some(p) => (
+                p.name.clone(),
+                self.task.as_ref().cloned().unwrap_or_default().name,
+            ),
+            None => (String::new(), String::new()),
+        };
+
+        Ok(Some(entries::Entry {
             id: self.id.clone(),
             description: self.description.clone(),
             billable: self.billable,
-            project: self.project.name.clone(),
-            task: self.task.as_ref().cloned().unwrap_or_default().name,
+            project,
+            task,
             tags: self
                 .tags
                 .clone()
                 .into_iter()
                 .map(|t| t.name.clone())
                 .collect(),
-            end: DateTime::parse_from_rfc3339(&self.time_interval.end)
-                .unwrap()
-                .to_utc(),
-            start: DateTime::parse_from_rfc3339(&self.time_interval.start)
-                .unwrap()
-                .to_utc(),
-        }
+            user: user.to_string(),
+            running: clipped,
+            end,
+            start,
+        }))
     }
 }
 
@@ -83,7 +234,8 @@ struct Project {
 #[derive(Deserialize, Debug)]
 struct TimeInterval {
     start: String,
-    end: String,
+    #[serde(default)]
+    end: Option<String>,
 }
 
 #[derive(Deserialize, Debug, Default, Clone)]
@@ -98,6 +250,81 @@ struct Tag {
     name: String,
 }
 
+impl Clockify {
+    fn workspaces(&self, workspaces: Vec<Workspace>) -> Result<Vec<Workspace>, ClockifyError> {
+        match self.workspace.as_deref() {
+            Some("all") => Ok(workspaces),
+            Some(w) => workspaces
+                .into_iter()
+                .find(|ws| ws.id == w || ws.name == w)
+                .map(|ws| vec![ws])
+                .ok_or(ClockifyError::WorkspaceNotFound(w.to_string())),
+            None if workspaces.len() > 1 => Err(ClockifyError::SeveralWorkspaces(
+                workspaces
+                    .iter()
+                    .map(|ws| format!("{} ({})", ws.name, ws.id))
+                    .collect(),
+            )),
+            None => Ok(workspaces),
+        }
+    }
+
+    /// Return the users whose entries are loaded
+    async fn users(&self, rest: &Rest, workspace: &str) -> Result<Vec<User>, Box<dyn Error>> {
+        if self.users.is_empty() {
+            return Ok(vec![rest.fetch::<User>("/user").await?]);
+        }
+
+        let users = rest
+            .paginate::<Vec<User>>(|page| {
+                format!("/workspaces/{workspace}/users?page={page}&page-size=100")
+            })
+            .await?;
+
+        if self.users == ["all"] {
+            return Ok(users);
+        }
+        self.users
+            .iter()
+            .map(|user| {
+                users
+                    .iter()
+                    .find(|u| &u.id == user || &u.email == user || &u.name == user)
+                    .cloned()
+                    .ok_or(ClockifyError::UserNotFound(user.clone()).into())
+            })
+            .collect()
+    }
+
+    /// Return the user attributed to the entries of each user id: empty when only the
+    /// authenticated user is loaded, otherwise the name, or the email without name,
+    /// followed by the email when several users have the same name
+    fn names<'a>(&self, users: impl Iterator<Item = &'a User>) -> HashMap<String, String> {
+        let users: HashMap<&str, &User> = users.map(|u| (u.id.as_str(), u)).collect();
+        let name = |u: &User| match u.name.is_empty() {
+            true => u.email.clone(),
+            false => u.name.clone(),
+        };
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for u in users.values() {
+            *counts.entry(name(u)).or_default() += 1;
+        }
+
+        users
+            .values()
+            .map(|u| {
+                let n = name(u);
+                let n = match (self.users.is_empty(), counts[&n] > 1) {
+                    (true, _) => String::new(),
+                    (false, true) => format!("{n} <{}>", u.email),
+                    (false, false) => n,
+                };
+                (u.id.clone(), n)
+            })
+            .collect()
+    }
+}
+
 #[async_trait(?Send)]
 impl Provider for Clockify {
     async fn load(
@@ -105,43 +332,442 @@ impl Provider for Clockify {
         start: DateTime<Utc>,
         end: DateTime<Utc>,
     ) -> Result<Vec<entries::Entry>, Box<dyn Error>> {
-        let mut entries: Vec<entries::Entry> = Vec::new();
-
         let mut headers = HeaderMap::new();
-        headers.append(
-            "content-type",
-            HeaderValue::from_str("application/json").expect("Hard coded values should be valid"),
-        );
         headers.append("X-Api-Key", HeaderValue::from_str(&self.token)?);
+        let rest = Rest::new(&self.base_url, headers)?.options(self.rest);
 
-        let base = "https://api.clockify.me/api/v1";
-        let client = Client::builder().default_headers(headers).build()?;
+        let workspaces = rest
+            .fetch::<Vec<Workspace>>("/workspaces")
+            .await
+            .map_err(ClockifyError::from_request)?;
 
-        let req = client.get(format!("{base}/workspaces")).build()?;
-        let workspace = client.execute(req).await?.json::<Vec<Workspace>>().await?;
-        let workspace = &workspace.first().unwrap().id;
+        let now = Utc::now().min(end);
+        let format = "%Y-%m-%dT%H:%M:%SZ";
+        let start = start.format(format).to_string();
+        let end = end.format(format).to_string();
 
-        let req = client.get(format!("{base}/user")).build()?;
-        let user = client.execute(req).await?.json::<User>().await?.id;
+        let mut workspace_users = Vec::new();
+        for workspace in self.workspaces(workspaces)? {
+            let users = self
+                .users(&rest, &workspace.id)
+                .await
+                .map_err(ClockifyError::from_request)?;
+            workspace_users.push((workspace.id, users));
+        }
+        // Users are told apart by their id, even when they have the same name
+        let names = self.names(workspace_users.iter().flat_map(|(_, users)| users));
 
-        let format = "%Y-%m-%dT%H:%M:%SZ";
-        let start = start.format(&format).to_string();
-        let end = end.format(&format).to_string();
-
-        let mut page = 1;
-        loop {
-            let req = client.get(format!("{base}/workspaces/{workspace}/user/{user}/time-entries?start={start}&end={end}&hydrated=true&page={page}&page-size=100")).build()?;
-            let body = client.execute(req).await?.text().await?;
-            let res: Vec<Entry> = serde_json::from_str(&body).unwrap();
-            if body.is_empty() || res.len() == 0 {
-                break;
-            }
-            for e in res {
-                entries.push(e.convert());
+        let mut entries = Vec::new();
+        for (workspace, users) in workspace_users {
+            for user in users {
+                let (user, name) = (&user.id, &names[&user.id]);
+                // Entries are read one by one to report which one is malformed
+                let values = rest
+                    .paginate::<Vec<serde_json::Value>>(|page| {
+                        format!("/workspaces/{workspace}/user/{user}/time-entries?start={start}&end={end}&hydrated=true&page={page}&page-size=100")
+                    })
+                    .await
+                    .map_err(ClockifyError::from_request)?;
+
+                for v in values {
+                    let id = v["id"].as_str().unwrap_or_default().to_string();
+                    let e: Entry =
+                        serde_json::from_value(v).map_err(|e| ClockifyError::MalformedEntry {
+                            id,
+                            reason: e.to_string(),
+                        })?;
+                    if let Some(e) = e.convert(name, self.running, now)? {
+                        entries.push(e);
+                    }
+                }
             }
-            page += 1;
         }
 
         Ok(entries)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use mockito::{Matcher, Server, ServerGuard};
+
+    use super::*;
+
+    const ENTRY: &str = r#"{"id": "e1", "description": "first", "billable": true,
+        "project": {"name": "Project1"}, "task": {"name": "Task1"}, "tags": [{"name": "Tag1"}],
+        "timeInterval": {"start": "2024-10-12T09:00:00Z", "end": "2024-10-12T10:00:00Z"}}"#;
+
+    async fn server() -> ServerGuard {
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/workspaces")
+            .match_header("X-Api-Key", "secret")
+            .with_body(r#"[{"id": "w1", "name": "First"}, {"id": "w2", "name": "Second"}]"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/user")
+            .with_body(r#"{"id": "u1", "name": "Me", "email": "me@example.com"}"#)
+            .create_async()
+            .await;
+        server
+    }
+
+    fn clockify(server: &ServerGuard, options: &[(&str, &str)]) -> Clockify {
+        let mut o = HashMap::from([
+            (String::from("base_url"), server.url()),
+            (String::from("token"), String::from("secret")),
+        ]);
+        for (k, v) in options {
+            o.insert(k.to_string(), v.to_string());
+        }
+        Clockify::new(o).unwrap()
+    }
+
+    async fn load(clockify: &mut Clockify) -> Result<Vec<entries::Entry>, Box<dyn Error>> {
+        clockify
+            .load(
+                Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 10, 31, 0, 0, 0).unwrap(),
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn several_workspaces() -> Result<(), Box<dyn Error>> {
+        let server = server().await;
+        let error = load(&mut clockify(&server, &[])).await.err().unwrap();
+        assert_eq!(
+            error.downcast_ref::<ClockifyError>(),
+            Some(&ClockifyError::SeveralWorkspaces(vec![
+                String::from("First (w1)"),
+                String::from("Second (w2)")
+            ]))
+        );
+
+        let error = load(&mut clockify(&server, &[("workspace", "Third")]))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.downcast_ref::<ClockifyError>(),
+            Some(&ClockifyError::WorkspaceNotFound(String::from("Third")))
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn workspace_by_name() -> Result<(), Box<dyn Error>> {
+        let mut server = server().await;
+        let mock = server
+            .mock("GET", "/workspaces/w2/user/u1/time-entries")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("start".into(), "2024-10-01T00:00:00Z".into()),
+                Matcher::UrlEncoded("end".into(), "2024-10-31T00:00:00Z".into()),
+                Matcher::UrlEncoded("page".into(), "1".into()),
+            ]))
+            .with_body(format!("[{ENTRY}]"))
+            .expect(1)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/workspaces/w2/user/u1/time-entries")
+            .match_query(Matcher::UrlEncoded("page".into(), "2".into()))
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        let entries = load(&mut clockify(&server, &[("workspace", "Second")])).await?;
+
+        mock.assert_async().await;
+        assert_eq!(
+            entries,
+            vec![entries::Entry {
+                id: String::from("e1"),
+                description: String::from("first"),
+                billable: true,
+                project: String::from("Project1"),
+                task: String::from("Task1"),
+                tags: vec![String::from("Tag1")],
+                start: Utc.with_ymd_and_hms(2024, 10, 12, 9, 0, 0).unwrap(),
+                user: String::new(),
+                running: false,
+                end: Utc.with_ymd_and_hms(2024, 10, 12, 10, 0, 0).unwrap(),
+            }]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn users() -> Result<(), Box<dyn Error>> {
+        let mut server = server().await;
+        for w in ["w1", "w2"] {
+            server
+                .mock("GET", format!("/workspaces/{w}/users").as_str())
+                .match_query(Matcher::Any)
+                .with_header("Last-Page", "true")
+                .with_body(
+                    r#"[{"id": "u1", "name": "Me", "email": "me@example.com"},
+                        {"id": "u2", "name": "Other", "email": "other@example.com"}]"#,
+                )
+                .create_async()
+                .await;
+            for u in ["u1", "u2"] {
+                let path = format!("/workspaces/{w}/user/{u}/time-entries");
+                server
+                    .mock("GET", path.as_str())
+                    .match_query(Matcher::UrlEncoded("page".into(), "1".into()))
+                    .with_body(format!("[{ENTRY}]"))
+                    .create_async()
+                    .await;
+                server
+                    .mock("GET", path.as_str())
+                    .match_query(Matcher::UrlEncoded("page".into(), "2".into()))
+                    .with_body("[]")
+                    .create_async()
+                    .await;
+            }
+        }
+
+        let entries = load(&mut clockify(
+            &server,
+            &[("workspace", "all"), ("user", "other@example.com")],
+        ))
+        .await?;
+        assert_eq!(
+            entries.iter().map(|e| e.user.as_str()).collect::<Vec<_>>(),
+            vec!["Other", "Other"]
+        );
+
+        let entries = load(&mut clockify(
+            &server,
+            &[("workspace", "w1"), ("user", "all")],
+        ))
+        .await?;
+        assert_eq!(
+            entries.iter().map(|e| e.user.as_str()).collect::<Vec<_>>(),
+            vec!["Me", "Other"]
+        );
+
+        let error = load(&mut clockify(
+            &server,
+            &[("workspace", "w1"), ("user", "nobody")],
+        ))
+        .await
+        .err()
+        .unwrap();
+        assert_eq!(
+            error.downcast_ref::<ClockifyError>(),
+            Some(&ClockifyError::UserNotFound(String::from("nobody")))
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn same_name() -> Result<(), Box<dyn Error>> {
+        let mut server = server().await;
+        server
+            .mock("GET", "/workspaces/w1/users")
+            .match_query(Matcher::Any)
+            .with_header("Last-Page", "true")
+            .with_body(
+                r#"[{"id": "u1", "name": "Me", "email": "me@example.com"},
+                    {"id": "u2", "name": "Me", "email": "other@example.com"},
+                    {"id": "u3", "name": "", "email": "third@example.com"}]"#,
+            )
+            .create_async()
+            .await;
+        for u in ["u1", "u2", "u3"] {
+            let path = format!("/workspaces/w1/user/{u}/time-entries");
+            server
+                .mock("GET", path.as_str())
+                .match_query(Matcher::Any)
+                .with_header("Last-Page", "true")
+                .with_body(format!("[{ENTRY}]"))
+                .create_async()
+                .await;
+        }
+
+        let entries = load(&mut clockify(
+            &server,
+            &[("workspace", "w1"), ("user", "all")],
+        ))
+        .await?;
+        assert_eq!(
+            entries.iter().map(|e| e.user.as_str()).collect::<Vec<_>>(),
+            vec![
+                "Me <me@example.com>",
+                "Me <other@example.com>",
+                "third@example.com"
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_options() {
+        assert_eq!(
+            Clockify::new(HashMap::new()).unwrap_err(),
+            OptionError::missing("Clockify", "token")
+        );
+        assert_eq!(
+            Clockify::new(HashMap::from([
+                (String::from("token"), String::from("secret")),
+                (String::from("running"), String::from("stop")),
+            ]))
+            .unwrap_err(),
+            OptionError::invalid("Clockify", "running", "stop", "skip, clip or error")
+        );
+    }
+
+    #[tokio::test]
+    async fn status_errors() -> Result<(), Box<dyn Error>> {
+        for (status, expected) in [
+            (401, ClockifyError::Unauthorized),
+            (429, ClockifyError::RateLimited),
+        ] {
+            let mut server = Server::new_async().await;
+            server
+                .mock("GET", "/workspaces")
+                .with_status(status)
+                .create_async()
+                .await;
+
+            let error = load(&mut clockify(&server, &[("backoff", "0")]))
+                .await
+                .err()
+                .unwrap();
+            assert_eq!(error.downcast_ref::<ClockifyError>(), Some(&expected));
+        }
+        Ok(())
+    }
+
+    async fn running_server() -> ServerGuard {
+        let mut server = server().await;
+        server
+            .mock("GET", "/workspaces/w1/user/u1/time-entries")
+            .match_query(Matcher::UrlEncoded("page".into(), "1".into()))
+            .with_header("Last-Page", "true")
+            .with_body(format!(
+                r#"[{ENTRY}, {{"id": "e2", "description": "running", "billable": true,
+                    "project": {{"name": "Project1"}},
+                    "timeInterval": {{"start": "2024-10-13T09:00:00Z", "end": null}}}}]"#
+            ))
+            .create_async()
+            .await;
+        server
+    }
+
+    #[tokio::test]
+    async fn running_timer() -> Result<(), Box<dyn Error>> {
+        let server = running_server().await;
+
+        let entries = load(&mut clockify(&server, &[("workspace", "w1")])).await?;
+        assert_eq!(entries.len(), 1);
+
+        let entries = load(&mut clockify(
+            &server,
+            &[("workspace", "w1"), ("running", "clip")],
+        ))
+        .await?;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[1].end,
+            Utc.with_ymd_and_hms(2024, 10, 31, 0, 0, 0).unwrap()
+        );
+        assert!(entries[1].running && !entries[0].running);
+
+        let error = load(&mut clockify(
+            &server,
+            &[("workspace", "w1"), ("running", "error")],
+        ))
+        .await
+        .err()
+        .unwrap();
+        assert_eq!(
+            error.downcast_ref::<ClockifyError>(),
+            Some(&ClockifyError::RunningTimer(String::from("e2")))
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn malformed_entry() -> Result<(), Box<dyn Error>> {
+        let mut server = server().await;
+        server
+            .mock("GET", "/workspaces/w1/user/u1/time-entries")
+            .match_query(Matcher::Any)
+            .with_header("Last-Page", "true")
+            .with_body(
+                r#"[{"id": "e3", "description": "", "billable": true, "project": {"name": "Project1"},
+                     "timeInterval": {"start": "yesterday", "end": "2024-10-12T10:00:00Z"}},
+                    {"id": "e4"}]"#,
+            )
+            .create_async()
+            .await;
+
+        let error = load(&mut clockify(&server, &[("workspace", "w1")]))
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(
+            error.downcast_ref::<ClockifyError>(),
+            Some(ClockifyError::MalformedEntry { id, .. }) if id == "e3"
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sequential_pages() -> Result<(), Box<dyn Error>> {
+        let mut server = server().await;
+        let path = "/workspaces/w1/user/u1/time-entries";
+        for (page, last) in [("1", "false"), ("2", "true")] {
+            server
+                .mock("GET", path)
+                .match_query(Matcher::UrlEncoded("page".into(), page.into()))
+                .with_header("Last-Page", last)
+                .with_body(format!("[{ENTRY}]"))
+                .expect(1)
+                .create_async()
+                .await;
+        }
+        let after_last = server
+            .mock("GET", path)
+            .match_query(Matcher::UrlEncoded("page".into(), "3".into()))
+            .expect(0)
+            .create_async()
+            .await;
+
+        let entries = load(&mut clockify(
+            &server,
+            &[("workspace", "w1"), ("concurrency", "8")],
+        ))
+        .await?;
+        assert_eq!(entries.len(), 2);
+        after_last.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn no_project() -> Result<(), Box<dyn Error>> {
+        let mut server = server().await;
+        server
+            .mock("GET", "/workspaces/w1/user/u1/time-entries")
+            .match_query(Matcher::Any)
+            .with_header("Last-Page", "true")
+            .with_body(
+                r#"[{"id": "e5", "billable": false, "project": null, "task": null,
+                     "timeInterval": {"start": "2024-10-12T09:00:00Z", "end": "2024-10-12T10:00:00Z"}}]"#,
+            )
+            .create_async()
+            .await;
+
+        let entries = load(&mut clockify(&server, &[("workspace", "w1")])).await?;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].project, "");
+        assert_eq!(entries[0].task, "");
+        assert_eq!(entries[0].description, "");
+        Ok(())
+    }
+}
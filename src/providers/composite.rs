@@ -0,0 +1,317 @@
+use super::{OptionError, Provider};
+use crate::entries;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::future::join_all;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// What to do when two providers return the same entry
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Duplicates {
+    /// Keep the entry of the provider given first
+    KeepFirst,
+    /// Keep the longest entry
+    KeepLongest,
+    /// Fail to load the entries
+    Error,
+}
+
+/// Load entries from several providers concurrently and merge them.
+///
+/// Two entries are duplicates when they have the same non-empty id within a provider, or
+/// the same interval and project. Duplicates are handled according to the `duplicates` option.
+pub struct Composite {
+    providers: Vec<Box<dyn Provider>>,
+    duplicates: Duplicates,
+}
+
+impl Composite {
+    pub fn new(
+        providers: Vec<Box<dyn Provider>>,
+        options: &HashMap<String, String>,
+    ) -> Result<Composite, OptionError> {
+        Ok(Composite {
+            providers,
+            duplicates: match options.get("duplicates").map(|d| d.as_str()) {
+                None | Some("keep-first") => Duplicates::KeepFirst,
+                Some("keep-longest") => Duplicates::KeepLongest,
+                Some("error") => Duplicates::Error,
+                Some(d) => {
+                    let expected = "keep-first, keep-longest or error";
+                    return Err(OptionError::invalid("Composite", "duplicates", d, expected));
+                }
+            },
+        })
+    }
+
+    /// Options of the provider `name`: options prefixed with `name.` are only given
+    /// to this provider, the others are given to every provider.
+    pub fn options(name: &str, options: &HashMap<String, String>) -> HashMap<String, String> {
+        let prefix = format!("{name}.");
+        let mut child: HashMap<String, String> = options
+            .iter()
+            .filter(|(k, _)| !k.contains('.'))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        for (k, v) in options.iter() {
+            if let Some(k) = k.strip_prefix(&prefix) {
+                child.insert(k.to_string(), v.clone());
+            }
+        }
+        child
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub struct DuplicateEntry {
+    pub first: String,
+    pub second: String,
+}
+
+impl Display for DuplicateEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Entries {} and {} are duplicates",
+            self.first, self.second
+        )
+    }
+}
+
+/// Merge entries given in the order of the providers, with the index of their provider.
+/// Ids are only compared within a provider and empty ids never match.
+fn merge(
+    entries: Vec<(usize, entries::Entry)>,
+    duplicates: Duplicates,
+) -> Result<Vec<entries::Entry>, DuplicateEntry> {
+    let mut merged: Vec<entries::Entry> = Vec::with_capacity(entries.len());
+    let mut ids: HashMap<(usize, String), usize> = HashMap::new();
+    let mut intervals: HashMap<(DateTime<Utc>, DateTime<Utc>, String), usize> = HashMap::new();
+
+    for (provider, e) in entries {
+        let id = (!e.id.is_empty()).then(|| (provider, e.id.clone()));
+        let interval = (e.start, e.end, e.project.clone());
+        let found = id
+            .as_ref()
+            .and_then(|id| ids.get(id))
+            .or_else(|| intervals.get(&interval))
+            .copied();
+
+        let i = match found {
+            None => {
+                merged.push(e);
+                merged.len() - 1
+            }
+            Some(i) => match duplicates {
+                Duplicates::KeepFirst => i,
+                Duplicates::KeepLongest => {
+                    if e.duration() > merged[i].duration() {
+                        merged[i] = e;
+                    }
+                    i
+                }
+                Duplicates::Error => {
+                    return Err(DuplicateEntry {
+                        first: merged[i].id.clone(),
+                        second: e.id,
+                    })
+                }
+            },
+        };
+        if let Some(id) = id {
+            ids.entry(id).or_insert(i);
+        }
+        intervals.entry(interval).or_insert(i);
+    }
+
+    Ok(merged)
+}
+
+#[async_trait(?Send)]
+impl Provider for Composite {
+    async fn load(
+        &mut self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<entries::Entry>, Box<dyn Error>> {
+        let loads = join_all(self.providers.iter_mut().map(|p| p.load(start, end))).await;
+
+        let mut entries = Vec::new();
+        for (i, l) in loads.into_iter().enumerate() {
+            entries.extend(l?.into_iter().map(|e| (i, e)));
+        }
+
+        Ok(merge(entries, self.duplicates)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeDelta, TimeZone};
+
+    use super::*;
+
+    struct TestProvider {
+        entries: Vec<entries::Entry>,
+    }
+
+    #[async_trait(?Send)]
+    impl Provider for TestProvider {
+        async fn load(
+            &mut self,
+            _: DateTime<Utc>,
+            _: DateTime<Utc>,
+        ) -> Result<Vec<entries::Entry>, Box<dyn Error>> {
+            Ok(self.entries.clone())
+        }
+    }
+
+    fn entry(id: &str, project: &str, hour: u32, hours: i64) -> entries::Entry {
+        let start = Utc.with_ymd_and_hms(2024, 10, 12, hour, 0, 0).unwrap();
+        entries::Entry {
+            id: id.to_string(),
+            description: String::new(),
+            billable: true,
+            project: project.to_string(),
+            task: String::new(),
+            tags: Vec::new(),
+            user: String::new(),
+            running: false,
+            end: start + TimeDelta::hours(hours),
+            start,
+        }
+    }
+
+    /// Entries of the first provider
+    fn first(entries: &[entries::Entry]) -> Vec<(usize, entries::Entry)> {
+        entries.iter().map(|e| (0, e.clone())).collect()
+    }
+
+    fn options(duplicates: &str) -> HashMap<String, String> {
+        HashMap::from([(String::from("duplicates"), duplicates.to_string())])
+    }
+
+    #[test]
+    fn child_options() {
+        let options = HashMap::from([
+            (String::from("token"), String::from("shared")),
+            (String::from("ics.path"), String::from("calendar.ics")),
+            (String::from("toggl.token"), String::from("toggl")),
+        ]);
+        assert_eq!(
+            Composite::options("toggl", &options),
+            HashMap::from([(String::from("token"), String::from("toggl"))])
+        );
+        assert_eq!(
+            Composite::options("ics", &options),
+            HashMap::from([
+                (String::from("token"), String::from("shared")),
+                (String::from("path"), String::from("calendar.ics")),
+            ])
+        );
+    }
+
+    #[test]
+    fn keep_first() {
+        let entries = vec![
+            entry("1", "Project1", 9, 1),
+            entry("2", "Project1", 10, 1),
+            entry("1", "Project2", 12, 2),
+            entry("3", "Project1", 10, 1),
+            entry("4", "Project2", 10, 1),
+        ];
+        assert_eq!(
+            merge(first(&entries), Duplicates::KeepFirst),
+            Ok(vec![
+                entries[0].clone(),
+                entries[1].clone(),
+                entries[4].clone()
+            ])
+        );
+    }
+
+    #[test]
+    fn keep_longest() {
+        let entries = vec![entry("1", "Project1", 9, 1), entry("1", "Project1", 9, 2)];
+        assert_eq!(
+            merge(first(&entries), Duplicates::KeepLongest),
+            Ok(vec![entries[1].clone()])
+        );
+    }
+
+    #[test]
+    fn error() {
+        let entries = vec![entry("1", "Project1", 9, 1), entry("2", "Project1", 9, 1)];
+        assert_eq!(
+            merge(first(&entries), Duplicates::Error),
+            Err(DuplicateEntry {
+                first: String::from("1"),
+                second: String::from("2"),
+            })
+        );
+    }
+
+    #[test]
+    fn ids_by_provider() {
+        let entries = vec![
+            (0, entry("1", "Project1", 9, 1)),
+            (1, entry("1", "Project2", 12, 2)),
+            (1, entry("", "Project1", 14, 1)),
+            (1, entry("", "Project2", 15, 1)),
+            (1, entry("2", "Project1", 9, 1)),
+        ];
+        assert_eq!(
+            merge(entries.clone(), Duplicates::Error),
+            Err(DuplicateEntry {
+                first: String::from("1"),
+                second: String::from("2"),
+            })
+        );
+        assert_eq!(
+            merge(entries[..4].to_vec(), Duplicates::Error),
+            Ok(entries[..4].iter().map(|(_, e)| e.clone()).collect())
+        );
+    }
+
+    #[test]
+    fn unknown_policy() {
+        assert_eq!(
+            Composite::new(Vec::new(), &options("keep-last")).err(),
+            Some(OptionError::invalid(
+                "Composite",
+                "duplicates",
+                "keep-last",
+                "keep-first, keep-longest or error"
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn load() -> Result<(), Box<dyn Error>> {
+        let mut composite = Composite::new(
+            vec![
+                Box::new(TestProvider {
+                    entries: vec![entry("1", "Project1", 9, 1)],
+                }),
+                Box::new(TestProvider {
+                    entries: vec![entry("1", "Project2", 13, 1), entry("2", "Project1", 9, 1)],
+                }),
+            ],
+            &options("keep-longest"),
+        )?;
+        let entries = composite
+            .load(
+                Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 10, 31, 0, 0, 0).unwrap(),
+            )
+            .await?;
+
+        assert_eq!(
+            entries,
+            vec![entry("1", "Project1", 9, 1), entry("1", "Project2", 13, 1)]
+        );
+        Ok(())
+    }
+}
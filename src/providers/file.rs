@@ -0,0 +1,321 @@
+use super::{OptionError, Provider};
+use crate::entries;
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs::read_to_string;
+use std::path::Path;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Format {
+    Json,
+    Csv,
+}
+
+/// Load entries previously exported into a JSON or CSV file.
+///
+/// JSON files contain an array of [`entries::Entry`].
+/// CSV files have the `id,description,billable,project,task,tags,start,end` columns,
+/// tags being separated by `;`, and an optional `user` column.
+#[derive(Debug)]
+pub struct File {
+    path: String,
+    format: Format,
+}
+
+impl File {
+    pub fn new(options: HashMap<String, String>) -> Result<File, OptionError> {
+        let path = options
+            .get("path")
+            .ok_or_else(|| OptionError::missing("File", "path"))?
+            .clone();
+
+        let format = match options.get("format") {
+            Some(f) => f.clone(),
+            None => Path::new(&path)
+                .extension()
+                .map(|e| e.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        };
+
+        let format = match format.to_lowercase().as_str() {
+            "csv" => Format::Csv,
+            "json" => Format::Json,
+            f => return Err(OptionError::invalid("File", "format", f, "json or csv")),
+        };
+
+        Ok(File { path, format })
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct CsvEntry {
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    billable: bool,
+    #[serde(default)]
+    project: String,
+    #[serde(default)]
+    task: String,
+    #[serde(default)]
+    tags: String,
+    #[serde(default)]
+    user: String,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+}
+
+impl CsvEntry {
+    fn convert(self) -> entries::Entry {
+        entries::Entry {
+            id: self.id,
+            description: self.description,
+            billable: self.billable,
+            project: self.project,
+            task: self.task,
+            tags: self
+                .tags
+                .split(';')
+                .filter(|t| !t.is_empty())
+                .map(String::from)
+                .collect(),
+            user: self.user,
+            running: false,
+            end: self.end,
+            start: self.start,
+        }
+    }
+}
+
+fn parse(content: &str, format: Format) -> Result<Vec<entries::Entry>, Box<dyn Error>> {
+    match format {
+        Format::Json => Ok(serde_json::from_str(content)?),
+        Format::Csv => {
+            let mut reader = csv::Reader::from_reader(content.as_bytes());
+            let mut entries = Vec::new();
+            for e in reader.deserialize::<CsvEntry>() {
+                entries.push(e?.convert());
+            }
+            Ok(entries)
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub struct InvalidEntry {
+    pub id: String,
+    pub reason: String,
+}
+
+impl Display for InvalidEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Entry {} is not valid: {}", self.id, self.reason)
+    }
+}
+
+#[async_trait(?Send)]
+impl Provider for File {
+    async fn load(
+        &mut self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<entries::Entry>, Box<dyn Error>> {
+        let content = read_to_string(&self.path)?;
+        let entries = parse(&content, self.format)?;
+
+        for e in &entries {
+            if e.end < e.start {
+                return Err(Box::new(InvalidEntry {
+                    id: e.id.clone(),
+                    reason: format!("ends ({}) before it starts ({})", e.end, e.start),
+                }));
+            }
+        }
+
+        Ok(entries
+            .into_iter()
+            .filter(|e| e.end > start && e.start < end)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env::temp_dir, fs::write};
+
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn options(path: &str) -> HashMap<String, String> {
+        HashMap::from([(String::from("path"), String::from(path))])
+    }
+
+    fn temp_file(name: &str, content: &str) -> String {
+        let path = temp_dir().join(name).to_string_lossy().to_string();
+        write(&path, content).unwrap();
+        path
+    }
+
+    fn expected() -> Vec<entries::Entry> {
+        vec![entries::Entry {
+            id: String::from("2"),
+            description: String::from("second"),
+            billable: true,
+            project: String::from("Project1"),
+            task: String::from("Task1"),
+            tags: vec![String::from("Tag1"), String::from("Tag2")],
+            start: Utc.with_ymd_and_hms(2024, 10, 12, 9, 0, 0).unwrap(),
+            user: String::new(),
+            running: false,
+            end: Utc.with_ymd_and_hms(2024, 10, 12, 10, 0, 0).unwrap(),
+        }]
+    }
+
+    #[test]
+    fn format_from_extension() {
+        assert_eq!(
+            File::new(options("entries.json")).unwrap().format,
+            Format::Json
+        );
+        assert_eq!(
+            File::new(options("entries.CSV")).unwrap().format,
+            Format::Csv
+        );
+    }
+
+    #[test]
+    fn format_from_options() {
+        let mut options = options("entries.txt");
+        options.insert(String::from("format"), String::from("csv"));
+        assert_eq!(File::new(options).unwrap().format, Format::Csv);
+    }
+
+    #[test]
+    fn invalid_options() {
+        assert_eq!(
+            File::new(options("entries.txt")).unwrap_err(),
+            OptionError::invalid("File", "format", "txt", "json or csv")
+        );
+        assert_eq!(
+            File::new(HashMap::new()).unwrap_err().to_string(),
+            "File provider options should contain path"
+        );
+    }
+
+    #[tokio::test]
+    async fn load_json() -> Result<(), Box<dyn Error>> {
+        let path = temp_file(
+            "ttm_file_provider.json",
+            r#"[
+                {"id": "1", "project": "Project1", "start": "2024-09-30T09:00:00Z", "end": "2024-09-30T10:00:00Z"},
+                {"id": "2", "description": "second", "billable": true, "project": "Project1", "task": "Task1",
+                 "tags": ["Tag1", "Tag2"], "start": "2024-10-12T11:00:00+02:00", "end": "2024-10-12T10:00:00Z"}
+            ]"#,
+        );
+
+        let mut file = File::new(options(&path)).unwrap();
+        let entries = file
+            .load(
+                Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 10, 31, 0, 0, 0).unwrap(),
+            )
+            .await?;
+
+        assert_eq!(entries, expected());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn load_csv() -> Result<(), Box<dyn Error>> {
+        let path = temp_file(
+            "ttm_file_provider.csv",
+            "id,description,billable,project,task,tags,start,end\n\
+             1,,false,Project1,,,2024-09-30T09:00:00Z,2024-09-30T10:00:00Z\n\
+             2,second,true,Project1,Task1,Tag1;Tag2,2024-10-12T09:00:00Z,2024-10-12T10:00:00Z\n",
+        );
+
+        let mut file = File::new(options(&path)).unwrap();
+        let entries = file
+            .load(
+                Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 10, 31, 0, 0, 0).unwrap(),
+            )
+            .await?;
+
+        assert_eq!(entries, expected());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn load_csv_users() -> Result<(), Box<dyn Error>> {
+        let path = temp_file(
+            "ttm_file_provider_users.csv",
+            "id,project,user,start,end\n\
+             1,Project1,alice,2024-10-12T09:00:00Z,2024-10-12T10:00:00Z\n\
+             2,Project1,bob,2024-10-12T09:00:00Z,2024-10-12T10:00:00Z\n",
+        );
+
+        let mut file = File::new(options(&path)).unwrap();
+        let entries = file
+            .load(
+                Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 10, 31, 0, 0, 0).unwrap(),
+            )
+            .await?;
+
+        assert_eq!(
+            entries.iter().map(|e| e.user.as_str()).collect::<Vec<_>>(),
+            vec!["alice", "bob"]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn invalid_interval() {
+        let path = temp_file(
+            "ttm_file_provider_invalid.json",
+            r#"[{"id": "1", "start": "2024-10-12T10:00:00Z", "end": "2024-10-12T09:00:00Z"}]"#,
+        );
+
+        let mut file = File::new(options(&path)).unwrap();
+        let error = file
+            .load(
+                Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 10, 31, 0, 0, 0).unwrap(),
+            )
+            .await
+            .err()
+            .unwrap();
+
+        assert_eq!(
+            error.downcast_ref::<InvalidEntry>().unwrap().id,
+            String::from("1")
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_timestamp() {
+        let path = temp_file(
+            "ttm_file_provider_timestamp.json",
+            r#"[{"id": "1", "start": "2024-10-12 10h", "end": "2024-10-12T09:00:00Z"}]"#,
+        );
+
+        let mut file = File::new(options(&path)).unwrap();
+        let res = file
+            .load(
+                Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 10, 31, 0, 0, 0).unwrap(),
+            )
+            .await;
+
+        assert!(res.is_err());
+    }
+}
@@ -0,0 +1,390 @@
+use super::{OptionError, Provider};
+use crate::entries;
+use async_trait::async_trait;
+use chrono::{DateTime, TimeDelta, Utc};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Infer entries from the commits of local git repositories.
+///
+/// Consecutive commits of the author separated by at most `gap` minutes form a
+/// session, which starts `first` minutes before its first commit to account for the
+/// work preceding it. The name of the repository or the branch becomes the project.
+/// Only the commits of the `author` option, or of the `user.email` of each repository,
+/// are counted.
+/// This is a best-effort estimation for people who do not track their time.
+#[derive(Debug)]
+pub struct Git {
+    repos: Vec<PathBuf>,
+    author: Option<String>,
+    gap: TimeDelta,
+    first: TimeDelta,
+    branch: bool,
+    billable: bool,
+}
+
+impl Git {
+    pub fn new(options: HashMap<String, String>) -> Result<Git, OptionError> {
+        let minutes = |key: &str, default: i64| match options.get(key) {
+            Some(m) => m
+                .parse::<u32>()
+                .ok()
+                .and_then(|m| TimeDelta::try_minutes(m.into()))
+                .ok_or_else(|| OptionError::invalid("Git", key, m, "a positive number of minutes")),
+            None => Ok(TimeDelta::minutes(default)),
+        };
+
+        Ok(Git {
+            repos: options
+                .get("repos")
+                .ok_or_else(|| OptionError::missing("Git", "repos"))?
+                .split(',')
+                .map(|r| PathBuf::from(r.trim()))
+                .collect(),
+            author: options.get("author").cloned(),
+            gap: minutes("gap", 120)?,
+            first: minutes("first", 30)?,
+            branch: match options.get("project").map(|p| p.as_str()) {
+                None | Some("repo") => false,
+                Some("branch") => true,
+                Some(p) => return Err(OptionError::invalid("Git", "project", p, "repo or branch")),
+            },
+            billable: match options.get("billable") {
+                Some(b) => b
+                    .parse()
+                    .map_err(|_| OptionError::invalid("Git", "billable", b, "a boolean"))?,
+                None => true,
+            },
+        })
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub struct GitError {
+    pub repo: String,
+    pub reason: String,
+}
+
+impl Display for GitError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Failed to read git repository {}: {}",
+            self.repo, self.reason
+        )
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+struct Commit {
+    hash: String,
+    time: DateTime<Utc>,
+    branch: String,
+    subject: String,
+}
+
+impl Commit {
+    /// Parse a line formatted with `%H%x09%at%x09%S%x09%s`
+    fn parse(line: &str) -> Option<Commit> {
+        let mut fields = line.splitn(4, '\t');
+        let hash = fields.next()?.to_string();
+        let time = DateTime::from_timestamp(fields.next()?.parse().ok()?, 0)?;
+        let source = fields.next()?;
+        let branch = source
+            .strip_prefix("refs/heads/")
+            .or_else(|| source.strip_prefix("refs/remotes/"))
+            .unwrap_or(source)
+            .to_string();
+
+        Some(Commit {
+            hash,
+            time,
+            branch,
+            subject: fields.next().unwrap_or_default().to_string(),
+        })
+    }
+}
+
+fn git(repo: &Path, args: &[&str]) -> Result<String, GitError> {
+    let error = |reason: String| GitError {
+        repo: repo.to_string_lossy().to_string(),
+        reason,
+    };
+
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .map_err(|e| error(e.to_string()))?;
+    if !output.status.success() {
+        return Err(error(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Group commits sorted by time into sessions of commits separated by at most `gap`
+fn sessions(commits: &[Commit], gap: TimeDelta) -> Vec<&[Commit]> {
+    let mut sessions = Vec::new();
+    let mut first = 0;
+    for i in 1..=commits.len() {
+        if i == commits.len() || commits[i].time - commits[i - 1].time > gap {
+            sessions.push(&commits[first..i]);
+            first = i;
+        }
+    }
+    sessions
+}
+
+impl Git {
+    fn commits(&self, repo: &Path, start: DateTime<Utc>) -> Result<Vec<Commit>, Box<dyn Error>> {
+        // Without an author, the commits of everyone would be counted
+        let author = match &self.author {
+            Some(a) => a.clone(),
+            None => git(repo, &["config", "user.email"])
+                .ok()
+                .map(|e| e.trim().to_string())
+                .filter(|e| !e.is_empty())
+                .ok_or_else(|| GitError {
+                    repo: repo.to_string_lossy().to_string(),
+                    reason: String::from("user.email is not set, use the author option"),
+                })?,
+        };
+
+        // The committer date filtered by --since is never before the author date,
+        // the author date of commits is checked against the interval afterwards
+        let since = format!("--since={}", (start - self.first).to_rfc3339());
+        let author = format!("--author={author}");
+        let args = [
+            "log",
+            "--all",
+            "--source",
+            "--no-merges",
+            "--format=%H%x09%at%x09%S%x09%s",
+            &since,
+            &author,
+        ];
+
+        Ok(git(repo, &args)?
+            .lines()
+            .filter_map(Commit::parse)
+            .collect())
+    }
+}
+
+#[async_trait(?Send)]
+impl Provider for Git {
+    async fn load(
+        &mut self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<entries::Entry>, Box<dyn Error>> {
+        let mut entries = Vec::new();
+
+        for repo in self.repos.iter() {
+            let name = repo
+                .canonicalize()
+                .ok()
+                .and_then(|r| r.file_name().map(|n| n.to_string_lossy().to_string()))
+                .unwrap_or(repo.to_string_lossy().to_string());
+
+            let mut commits: Vec<Commit> = self
+                .commits(repo, start)?
+                .into_iter()
+                .filter(|c| c.time >= start && c.time < end)
+                .collect();
+            commits.sort_by_key(|c| c.time);
+
+            // Sessions are computed for each project so that work on several branches
+            // is not merged when the branch is the project
+            let mut by_project: HashMap<String, Vec<Commit>> = HashMap::new();
+            for c in commits {
+                let project = match self.branch {
+                    true => c.branch.clone(),
+                    false => name.clone(),
+                };
+                by_project.entry(project).or_default().push(c);
+            }
+
+            for (project, commits) in by_project {
+                for session in sessions(&commits, self.gap) {
+                    let first = &session[0];
+                    let last = &session[session.len() - 1];
+                    entries.push(entries::Entry {
+                        id: first.hash.clone(),
+                        description: session
+                            .iter()
+                            .map(|c| c.subject.as_str())
+                            .collect::<Vec<_>>()
+                            .join("; "),
+                        billable: self.billable,
+                        project: project.clone(),
+                        task: match self.branch {
+                            true => String::new(),
+                            false => last.branch.clone(),
+                        },
+                        tags: Vec::new(),
+                        user: String::new(),
+                        running: false,
+                        end: last.time,
+                        start: first.time - self.first,
+                    });
+                }
+            }
+        }
+
+        entries.sort_by_key(|e| e.start);
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use std::env;
+
+    use super::*;
+
+    fn commit(hash: &str, hour: u32, minute: u32) -> Commit {
+        Commit {
+            hash: hash.to_string(),
+            time: Utc.with_ymd_and_hms(2024, 10, 12, hour, minute, 0).unwrap(),
+            branch: String::from("main"),
+            subject: hash.to_string(),
+        }
+    }
+
+    #[test]
+    fn parse_commit() {
+        assert_eq!(
+            Commit::parse("abc\t1728723600\trefs/heads/main\tFix\tbug"),
+            Some(Commit {
+                hash: String::from("abc"),
+                time: Utc.with_ymd_and_hms(2024, 10, 12, 9, 0, 0).unwrap(),
+                branch: String::from("main"),
+                subject: String::from("Fix\tbug"),
+            })
+        );
+        assert_eq!(Commit::parse("abc\tnot a time\tmain\tFix"), None);
+    }
+
+    #[test]
+    fn group_sessions() {
+        let commits = vec![
+            commit("a", 9, 0),
+            commit("b", 10, 30),
+            commit("c", 11, 0),
+            commit("d", 14, 0),
+        ];
+        let sessions = sessions(&commits, TimeDelta::minutes(90));
+        assert_eq!(sessions, vec![&commits[0..3], &commits[3..4]]);
+        assert!(super::sessions(&[], TimeDelta::minutes(90)).is_empty());
+    }
+
+    #[test]
+    fn invalid_options() {
+        let options = |key: &str, value: &str| {
+            HashMap::from([
+                (String::from("repos"), String::from(".")),
+                (key.to_string(), value.to_string()),
+            ])
+        };
+        assert_eq!(
+            Git::new(options("gap", "1h")).unwrap_err(),
+            OptionError::invalid("Git", "gap", "1h", "a positive number of minutes")
+        );
+        assert_eq!(
+            Git::new(options("first", "-30")).unwrap_err(),
+            OptionError::invalid("Git", "first", "-30", "a positive number of minutes")
+        );
+        assert!(Git::new(options("gap", "99999999999999999999")).is_err());
+        assert!(Git::new(options("project", "tag")).is_err());
+        assert!(Git::new(options("first", "15")).is_ok());
+        assert!(Git::new(HashMap::new()).is_err());
+    }
+
+    #[tokio::test]
+    async fn load() -> Result<(), Box<dyn Error>> {
+        let repo = env::temp_dir().join("ttm_git");
+        let _ = std::fs::remove_dir_all(&repo);
+        std::fs::create_dir_all(&repo)?;
+        git(&repo, &["init", "-q", "-b", "main"])?;
+        for (date, email) in [
+            ("2024-10-12T09:00:00Z", "me@example.com"),
+            ("2024-10-12T09:40:00Z", "me@example.com"),
+            ("2024-10-12T10:00:00Z", "other@example.com"),
+            ("2024-10-12T15:00:00Z", "me@example.com"),
+        ] {
+            let output = Command::new("git")
+                .arg("-C")
+                .arg(&repo)
+                .args(["commit", "-q", "--allow-empty", "-m", date])
+                .env("GIT_AUTHOR_NAME", "Someone")
+                .env("GIT_AUTHOR_EMAIL", email)
+                .env("GIT_AUTHOR_DATE", date)
+                .env("GIT_COMMITTER_NAME", "Someone")
+                .env("GIT_COMMITTER_EMAIL", email)
+                .env("GIT_COMMITTER_DATE", date)
+                .output()?;
+            assert!(output.status.success());
+        }
+
+        let mut provider = Git::new(HashMap::from([
+            (String::from("repos"), repo.to_string_lossy().to_string()),
+            (String::from("author"), String::from("me@example.com")),
+            (String::from("gap"), String::from("60")),
+        ]))?;
+        let entries = provider
+            .load(
+                Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 10, 31, 0, 0, 0).unwrap(),
+            )
+            .await?;
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].project, "ttm_git");
+        assert_eq!(entries[0].task, "main");
+        assert_eq!(
+            entries[0].description,
+            "2024-10-12T09:00:00Z; 2024-10-12T09:40:00Z"
+        );
+        assert_eq!(
+            entries[0].start,
+            Utc.with_ymd_and_hms(2024, 10, 12, 8, 30, 0).unwrap()
+        );
+        assert_eq!(
+            entries[0].end,
+            Utc.with_ymd_and_hms(2024, 10, 12, 9, 40, 0).unwrap()
+        );
+        assert_eq!(entries[1].duration(), TimeDelta::minutes(30));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn missing_author() -> Result<(), Box<dyn Error>> {
+        let repo = env::temp_dir().join("ttm_git_author");
+        let _ = std::fs::remove_dir_all(&repo);
+        std::fs::create_dir_all(&repo)?;
+        git(&repo, &["init", "-q"])?;
+        git(&repo, &["config", "user.email", ""])?;
+
+        let mut provider = Git::new(HashMap::from([(
+            String::from("repos"),
+            repo.to_string_lossy().to_string(),
+        )]))?;
+        let error = provider
+            .load(
+                Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 10, 31, 0, 0, 0).unwrap(),
+            )
+            .await
+            .unwrap_err();
+        assert!(error.downcast_ref::<GitError>().is_some());
+        Ok(())
+    }
+}
@@ -0,0 +1,274 @@
+use super::rest::{Page, Rest, RestOptions};
+use super::{OptionError, Provider};
+use crate::entries;
+use crate::utils::{local_timezone, midnight};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
+use reqwest::header::HeaderMap;
+use reqwest::header::HeaderValue;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::error::Error;
+
+const BASE_URL: &str = "https://api.harvestapp.com/v2";
+
+/// Load the time entries of the authenticated user from Harvest.
+///
+/// Harvest entries are durations within a day: they start at the time they were
+/// started when timestamps are enabled in Harvest, at midnight otherwise, in the
+/// `timezone` option (the --timezone argument or the system timezone by default).
+/// Running entries are ignored.
+#[derive(Debug)]
+pub struct Harvest {
+    base_url: String,
+    token: String,
+    account: String,
+    timezone: Tz,
+    rest: RestOptions,
+}
+
+impl Harvest {
+    pub fn new(options: HashMap<String, String>) -> Result<Harvest, OptionError> {
+        Ok(Harvest {
+            base_url: options
+                .get("base_url")
+                .cloned()
+                .unwrap_or(String::from(BASE_URL)),
+            token: options
+                .get("token")
+                .ok_or_else(|| OptionError::missing("Harvest", "token"))?
+                .clone(),
+            account: options
+                .get("account")
+                .ok_or_else(|| OptionError::missing("Harvest", "account"))?
+                .clone(),
+            timezone: match options.get("timezone") {
+                Some(tz) => tz.parse().map_err(|_| {
+                    OptionError::invalid("Harvest", "timezone", tz, "an IANA timezone")
+                })?,
+                None => local_timezone(),
+            },
+            rest: RestOptions::new(&options).map_err(|e| e.of("Harvest"))?,
+        })
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct User {
+    id: u64,
+}
+
+#[derive(Deserialize, Debug)]
+struct Named {
+    name: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct TimeEntry {
+    id: u64,
+    spent_date: NaiveDate,
+    hours: f64,
+    #[serde(default)]
+    notes: Option<String>,
+    billable: bool,
+    #[serde(default)]
+    is_running: bool,
+    #[serde(default)]
+    started_time: Option<String>,
+    project: Named,
+    task: Named,
+}
+
+impl TimeEntry {
+    /// Return None for running time entries since they have no end yet.
+    /// Times are wall-clock times of the timezone.
+    fn convert(self, tz: &Tz) -> Option<entries::Entry> {
+        if self.is_running {
+            return None;
+        }
+
+        let time = self
+            .started_time
+            .and_then(|t| NaiveTime::parse_from_str(&t.to_uppercase(), "%I:%M%p").ok());
+        let start = match time {
+            Some(time) => tz
+                .from_local_datetime(&self.spent_date.and_time(time))
+                .earliest()
+                .map(|d| d.to_utc())
+                .unwrap_or_else(|| midnight(tz, self.spent_date)),
+            None => midnight(tz, self.spent_date),
+        };
+
+        Some(entries::Entry {
+            id: self.id.to_string(),
+            description: self.notes.unwrap_or_default(),
+            billable: self.billable,
+            project: self.project.name,
+            task: self.task.name,
+            tags: Vec::new(),
+            user: String::new(),
+            running: false,
+            end: start + TimeDelta::seconds((self.hours * 3600.0).round() as i64),
+            start,
+        })
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct TimeEntries {
+    time_entries: Vec<TimeEntry>,
+    next_page: Option<u32>,
+    #[serde(default)]
+    total_pages: Option<u32>,
+}
+
+impl Page for TimeEntries {
+    type Item = TimeEntry;
+
+    fn is_last(&self, _: u32, _: &HeaderMap) -> bool {
+        self.next_page.is_none()
+    }
+
+    fn items(self) -> Vec<Self::Item> {
+        self.time_entries
+    }
+
+    fn total_pages(&self, _: &HeaderMap) -> Option<u32> {
+        self.total_pages
+    }
+}
+
+#[async_trait(?Send)]
+impl Provider for Harvest {
+    async fn load(
+        &mut self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<entries::Entry>, Box<dyn Error>> {
+        let mut headers = HeaderMap::new();
+        headers.append(
+            "Authorization",
+            HeaderValue::from_str(&format!("Bearer {}", self.token))?,
+        );
+        headers.append("Harvest-Account-Id", HeaderValue::from_str(&self.account)?);
+        headers.append(
+            "User-Agent",
+            HeaderValue::from_str("time-tracking-manager")
+                .expect("Hard coded values should be valid"),
+        );
+        let rest = Rest::new(&self.base_url, headers)?.options(self.rest);
+
+        let user = rest.fetch::<User>("/users/me").await?.id;
+
+        let format = "%Y-%m-%d";
+        let from = start
+            .with_timezone(&self.timezone)
+            .format(format)
+            .to_string();
+        let to = end.with_timezone(&self.timezone).format(format).to_string();
+
+        let entries = rest
+            .paginate::<TimeEntries>(|page| {
+                format!("/time_entries?user_id={user}&from={from}&to={to}&page={page}&per_page=100")
+            })
+            .await?
+            .into_iter()
+            .filter_map(|e| e.convert(&self.timezone))
+            .collect();
+
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use mockito::{Matcher, Server};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn load() -> Result<(), Box<dyn Error>> {
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/users/me")
+            .match_header("Authorization", "Bearer secret")
+            .match_header("Harvest-Account-Id", "42")
+            .with_body(r#"{"id": 7}"#)
+            .create_async()
+            .await;
+
+        let query = |page: &str| {
+            Matcher::AllOf(vec![
+                Matcher::UrlEncoded("user_id".into(), "7".into()),
+                Matcher::UrlEncoded("from".into(), "2024-10-01".into()),
+                Matcher::UrlEncoded("to".into(), "2024-10-31".into()),
+                Matcher::UrlEncoded("page".into(), page.into()),
+            ])
+        };
+        let first = server
+            .mock("GET", "/time_entries")
+            .match_query(query("1"))
+            .with_body(
+                r#"{"time_entries": [
+                        {"id": 1, "spent_date": "2024-10-12", "hours": 1.5, "notes": "first",
+                         "billable": true, "is_running": false, "started_time": "9:00am",
+                         "project": {"id": 1, "name": "Project1"}, "task": {"id": 1, "name": "Task1"}}
+                    ], "next_page": 2}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let second = server
+            .mock("GET", "/time_entries")
+            .match_query(query("2"))
+            .with_body(
+                r#"{"time_entries": [
+                        {"id": 2, "spent_date": "2024-10-13", "hours": 2.0, "notes": null,
+                         "billable": false, "is_running": false, "started_time": null,
+                         "project": {"id": 1, "name": "Project1"}, "task": {"id": 2, "name": "Task2"}},
+                        {"id": 3, "spent_date": "2024-10-14", "hours": 0.5, "billable": true,
+                         "is_running": true, "started_time": "10:00am",
+                         "project": {"id": 1, "name": "Project1"}, "task": {"id": 2, "name": "Task2"}}
+                    ], "next_page": null}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let mut harvest = Harvest::new(HashMap::from([
+            (String::from("base_url"), server.url()),
+            (String::from("token"), String::from("secret")),
+            (String::from("account"), String::from("42")),
+            (String::from("timezone"), String::from("Europe/Paris")),
+        ]))?;
+        let entries = harvest
+            .load(
+                Utc.with_ymd_and_hms(2024, 9, 30, 22, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 10, 31, 22, 0, 0).unwrap(),
+            )
+            .await?;
+
+        first.assert_async().await;
+        second.assert_async().await;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].description, "first");
+        assert_eq!(entries[0].task, "Task1");
+        assert_eq!(
+            entries[0].start,
+            Utc.with_ymd_and_hms(2024, 10, 12, 7, 0, 0).unwrap()
+        );
+        assert_eq!(
+            entries[0].end,
+            Utc.with_ymd_and_hms(2024, 10, 12, 8, 30, 0).unwrap()
+        );
+        assert!(!entries[1].billable);
+        assert_eq!(
+            entries[1].start,
+            Utc.with_ymd_and_hms(2024, 10, 12, 22, 0, 0).unwrap()
+        );
+        assert_eq!(entries[1].duration(), TimeDelta::hours(2));
+        Ok(())
+    }
+}
@@ -0,0 +1,1000 @@
+use super::{OptionError, Provider};
+use crate::entries;
+use crate::utils::local_timezone;
+use async_trait::async_trait;
+use chrono::{
+    DateTime, Datelike, Days, Local, LocalResult, Months, NaiveDate, NaiveDateTime, NaiveTime,
+    TimeDelta, TimeZone, Utc, Weekday,
+};
+use chrono_tz::Tz;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs::read_to_string;
+
+/// Load time blocks from the events of a local iCalendar (.ics) file.
+///
+/// SUMMARY is matched against `pattern` (`{project}` by default) to fill the project,
+/// the task and the description of the entries. CATEGORIES are used as tags by default
+/// or as project or task when `categories` is set accordingly.
+/// Recurring events are expanded within the requested interval, moved occurrences
+/// (RECURRENCE-ID) replacing the original ones.
+/// All-day and cancelled events are ignored. Floating times are read in `timezone`.
+#[derive(Debug)]
+pub struct Ics {
+    path: String,
+    pattern: Pattern,
+    categories: Categories,
+    billable: bool,
+    timezone: Tz,
+}
+
+impl Ics {
+    pub fn new(options: HashMap<String, String>) -> Result<Ics, OptionError> {
+        Ok(Ics {
+            path: options
+                .get("path")
+                .ok_or_else(|| OptionError::missing("Ics", "path"))?
+                .clone(),
+            pattern: Pattern::new(options.get("pattern").map_or("{project}", |p| p.as_str())),
+            categories: match options.get("categories").map(|c| c.as_str()) {
+                None | Some("tags") => Categories::Tags,
+                Some("project") => Categories::Project,
+                Some("task") => Categories::Task,
+                Some(c) => {
+                    let expected = "tags, project or task";
+                    return Err(OptionError::invalid("Ics", "categories", c, expected));
+                }
+            },
+            billable: match options.get("billable") {
+                Some(b) => b
+                    .parse()
+                    .map_err(|_| OptionError::invalid("Ics", "billable", b, "a boolean"))?,
+                None => true,
+            },
+            timezone: match options.get("timezone") {
+                Some(tz) => tz
+                    .parse()
+                    .map_err(|_| OptionError::invalid("Ics", "timezone", tz, "an IANA timezone"))?,
+                None => local_timezone(),
+            },
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Categories {
+    Tags,
+    Project,
+    Task,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Segment {
+    Literal(String),
+    Field(String),
+}
+
+/// Template such as `{project} - {task}` used to extract fields from a text
+#[derive(Debug, PartialEq, Clone)]
+struct Pattern {
+    segments: Vec<Segment>,
+}
+
+impl Pattern {
+    fn new(pattern: &str) -> Pattern {
+        let mut segments = Vec::new();
+        let mut rest = pattern;
+
+        while let Some(open) = rest.find('{') {
+            let Some(close) = rest[open..].find('}') else {
+                break;
+            };
+            if open > 0 {
+                segments.push(Segment::Literal(rest[..open].to_string()));
+            }
+            segments.push(Segment::Field(rest[open + 1..open + close].to_string()));
+            rest = &rest[open + close + 1..];
+        }
+        if !rest.is_empty() {
+            segments.push(Segment::Literal(rest.to_string()));
+        }
+
+        Pattern { segments }
+    }
+
+    /// Extract the fields from the given text.
+    /// Return None if the text does not match the pattern.
+    fn captures(&self, text: &str) -> Option<HashMap<String, String>> {
+        let mut fields = HashMap::new();
+        let mut rest = text;
+        let mut field: Option<&String> = None;
+
+        for s in &self.segments {
+            match s {
+                Segment::Literal(l) => {
+                    let i = match field {
+                        Some(_) => rest.find(l.as_str())?,
+                        None if rest.starts_with(l.as_str()) => 0,
+                        None => return None,
+                    };
+                    if let Some(f) = field.take() {
+                        fields.insert(f.clone(), rest[..i].trim().to_string());
+                    }
+                    rest = &rest[i + l.len()..];
+                }
+                Segment::Field(f) => {
+                    if let Some(previous) = field.replace(f) {
+                        fields.insert(previous.clone(), String::new());
+                    }
+                }
+            }
+        }
+
+        match field {
+            Some(f) => {
+                fields.insert(f.clone(), rest.trim().to_string());
+            }
+            None if !rest.is_empty() => return None,
+            None => {}
+        }
+
+        Some(fields)
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub struct IcsError {
+    pub line: usize,
+    pub reason: String,
+}
+
+impl Display for IcsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Invalid iCalendar at line {}: {}",
+            self.line, self.reason
+        )
+    }
+}
+
+/// Date or date-time value of an event, as written in the calendar
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Time {
+    /// Whole day, without time, interpreted in the local time zone
+    Date(NaiveDate),
+    /// UTC date-time
+    Utc(NaiveDateTime),
+    /// Date-time in the time zone given by its TZID parameter
+    Zoned(NaiveDateTime, Tz),
+    /// Floating date-time, interpreted in the local time zone
+    Local(NaiveDateTime),
+}
+
+impl Time {
+    fn parse(value: &str, params: &HashMap<String, String>) -> Result<Time, String> {
+        if params.get("VALUE").map(|v| v.as_str()) == Some("DATE") || value.len() == 8 {
+            return NaiveDate::parse_from_str(value, "%Y%m%d")
+                .map(Time::Date)
+                .map_err(|e| format!("{value}: {e}"));
+        }
+        let (value, utc) = match value.strip_suffix('Z') {
+            Some(v) => (v, true),
+            None => (value, false),
+        };
+        let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+            .map_err(|e| format!("{value}: {e}"))?;
+        Ok(match (utc, params.get("TZID")) {
+            (true, _) => Time::Utc(naive),
+            (false, Some(tzid)) => Time::Zoned(
+                naive,
+                tzid.trim_start_matches('/')
+                    .parse()
+                    .map_err(|_| format!("unknown time zone {tzid}"))?,
+            ),
+            (false, None) => Time::Local(naive),
+        })
+    }
+
+    fn naive(&self) -> NaiveDateTime {
+        match self {
+            Time::Date(d) => d.and_time(NaiveTime::MIN),
+            Time::Utc(n) | Time::Zoned(n, _) | Time::Local(n) => *n,
+        }
+    }
+
+    /// Same kind of value at another wall-clock time
+    fn with_naive(&self, naive: NaiveDateTime) -> Time {
+        match self {
+            Time::Date(_) => Time::Date(naive.date()),
+            Time::Utc(_) => Time::Utc(naive),
+            Time::Zoned(_, tz) => Time::Zoned(naive, *tz),
+            Time::Local(_) => Time::Local(naive),
+        }
+    }
+
+    pub fn to_utc(&self) -> DateTime<Utc> {
+        self.to_utc_in(&Local)
+    }
+
+    /// UTC time, dates and floating date-times being interpreted in the given time zone
+    pub fn to_utc_in<Z: TimeZone>(&self, zone: &Z) -> DateTime<Utc> {
+        let resolve =
+            |t: LocalResult<DateTime<Utc>>| t.earliest().unwrap_or_else(|| self.naive().and_utc());
+        match self {
+            Time::Utc(n) => n.and_utc(),
+            Time::Zoned(n, tz) => resolve(tz.from_local_datetime(n).map(|d| d.to_utc())),
+            Time::Date(_) | Time::Local(_) => {
+                resolve(zone.from_local_datetime(&self.naive()).map(|d| d.to_utc()))
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// Subset of the recurrence rules (RFC 5545 RRULE) used by calendar applications
+#[derive(Debug, PartialEq, Clone)]
+pub struct RRule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub count: Option<u32>,
+    pub until: Option<Time>,
+    pub by_day: Vec<Weekday>,
+}
+
+impl RRule {
+    fn parse(value: &str) -> Result<RRule, String> {
+        let mut frequency = None;
+        let mut rule = RRule {
+            frequency: Frequency::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            by_day: Vec::new(),
+        };
+
+        for part in value.split(';') {
+            let (k, v) = part
+                .split_once('=')
+                .ok_or_else(|| format!("invalid recurrence rule part {part}"))?;
+            match k {
+                "FREQ" => {
+                    frequency = Some(match v {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return Err(format!("unsupported recurrence frequency {v}")),
+                    })
+                }
+                "INTERVAL" => rule.interval = v.parse().map_err(|_| format!("invalid {part}"))?,
+                "COUNT" => rule.count = Some(v.parse().map_err(|_| format!("invalid {part}"))?),
+                "UNTIL" => rule.until = Some(Time::parse(v, &HashMap::new())?),
+                "BYDAY" => {
+                    for d in v.split(',') {
+                        // Ordinal prefixes (such as 1MO) are not supported
+                        let d = d.trim_start_matches(|c: char| {
+                            c.is_ascii_digit() || c == '-' || c == '+'
+                        });
+                        rule.by_day.push(match d {
+                            "MO" => Weekday::Mon,
+                            "TU" => Weekday::Tue,
+                            "WE" => Weekday::Wed,
+                            "TH" => Weekday::Thu,
+                            "FR" => Weekday::Fri,
+                            "SA" => Weekday::Sat,
+                            "SU" => Weekday::Sun,
+                            _ => return Err(format!("invalid day {d} in {part}")),
+                        });
+                    }
+                    rule.by_day.sort_by_key(|d| d.num_days_from_monday());
+                }
+                _ => {}
+            }
+        }
+
+        rule.frequency = frequency.ok_or("recurrence rule without frequency")?;
+        if rule.interval == 0 {
+            return Err(String::from("recurrence interval should be positive"));
+        }
+        Ok(rule)
+    }
+
+    /// Start of the n-th period after the first occurrence
+    fn period(&self, first: NaiveDateTime, n: u32) -> Option<NaiveDateTime> {
+        let n = n.checked_mul(self.interval)?;
+        match self.frequency {
+            Frequency::Daily => first.checked_add_days(Days::new(n.into())),
+            Frequency::Weekly => first.checked_add_days(Days::new(7 * u64::from(n))),
+            Frequency::Monthly => first.checked_add_months(Months::new(n)),
+            Frequency::Yearly => first.checked_add_months(Months::new(n.checked_mul(12)?)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Event {
+    pub uid: String,
+    pub summary: String,
+    pub description: String,
+    pub categories: Vec<String>,
+    pub cancelled: bool,
+    pub start: Time,
+    pub end: Time,
+    pub rrule: Option<RRule>,
+    pub exdates: Vec<Time>,
+    /// Occurrence of a recurring event replaced by this event
+    pub recurrence_id: Option<Time>,
+}
+
+impl Event {
+    pub fn all_day(&self) -> bool {
+        matches!(self.start, Time::Date(_))
+    }
+
+    /// Start and end of the occurrences of the event overlapping the given interval
+    pub fn occurrences(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        self.occurrences_in(start, end, &Local)
+    }
+
+    /// Occurrences of the event, dates and floating date-times being interpreted in the
+    /// given time zone
+    pub fn occurrences_in<Z: TimeZone>(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        zone: &Z,
+    ) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        let first = self.start.naive();
+        let duration = self.end.naive() - first;
+        let overlaps = |s: &Time| {
+            let s = s.to_utc_in(zone);
+            s < end && s + duration > start
+        };
+
+        let rule = match &self.rrule {
+            Some(rule) => rule,
+            None => {
+                return match overlaps(&self.start) {
+                    true => vec![(self.start.to_utc_in(zone), self.end.to_utc_in(zone))],
+                    false => vec![],
+                }
+            }
+        };
+
+        let exdates: Vec<DateTime<Utc>> = self.exdates.iter().map(|e| e.to_utc_in(zone)).collect();
+        let until = rule.until.map(|u| u.to_utc_in(zone));
+        let mut occurrences = Vec::new();
+        let mut count = 0;
+
+        for n in 0.. {
+            let Some(period) = rule.period(first, n) else {
+                break;
+            };
+
+            let candidates = match (rule.frequency, rule.by_day.is_empty()) {
+                (Frequency::Weekly, false) => {
+                    let monday = period.date()
+                        - TimeDelta::days(period.weekday().num_days_from_monday().into());
+                    rule.by_day
+                        .iter()
+                        .map(|d| {
+                            (monday + TimeDelta::days(d.num_days_from_monday().into()))
+                                .and_time(first.time())
+                        })
+                        .filter(|c| *c >= first)
+                        .collect()
+                }
+                (_, false) => match rule.by_day.contains(&period.weekday()) {
+                    true => vec![period],
+                    false => vec![],
+                },
+                (_, true) => vec![period],
+            };
+
+            for c in candidates {
+                let occurrence = self.start.with_naive(c);
+                let utc = occurrence.to_utc_in(zone);
+
+                if until.is_some_and(|u| utc > u) || rule.count.is_some_and(|n| count >= n) {
+                    return occurrences;
+                }
+                count += 1;
+
+                if exdates.contains(&utc) || !overlaps(&occurrence) {
+                    continue;
+                }
+                occurrences.push((utc, self.end.with_naive(c + duration).to_utc_in(zone)));
+            }
+
+            if self.start.with_naive(period).to_utc_in(zone) >= end {
+                break;
+            }
+        }
+
+        occurrences
+    }
+}
+
+/// Unescape TEXT values
+fn unescape(value: &str) -> String {
+    let mut s = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            s.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => s.push('\n'),
+            Some(c) => s.push(c),
+            None => {}
+        }
+    }
+    s
+}
+
+/// Split a TEXT list on unescaped commas
+fn split_list(value: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut escaped = false;
+    for c in value.chars() {
+        if escaped {
+            current.push('\\');
+            current.push(c);
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == ',' {
+            items.push(unescape(&current));
+            current.clear();
+        } else {
+            current.push(c);
+        }
+    }
+    items.push(unescape(&current));
+    items.into_iter().filter(|i| !i.is_empty()).collect()
+}
+
+/// Parse a duration such as `PT1H30M` or `P1D`
+fn parse_duration(value: &str) -> Result<TimeDelta, String> {
+    let invalid = || format!("invalid duration {value}");
+    let (sign, value) = match value.strip_prefix('-') {
+        Some(v) => (-1, v),
+        None => (1, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let value = value.strip_prefix('P').ok_or_else(invalid)?;
+
+    let mut duration = TimeDelta::zero();
+    let mut number = String::new();
+    for c in value.chars() {
+        match c {
+            'T' => {}
+            '0'..='9' => number.push(c),
+            _ => {
+                let n: i64 = number.parse().map_err(|_| invalid())?;
+                number.clear();
+                duration += match c {
+                    'W' => TimeDelta::weeks(n),
+                    'D' => TimeDelta::days(n),
+                    'H' => TimeDelta::hours(n),
+                    'M' => TimeDelta::minutes(n),
+                    'S' => TimeDelta::seconds(n),
+                    _ => return Err(invalid()),
+                };
+            }
+        }
+    }
+    Ok(duration * sign)
+}
+
+/// Parse the events of an iCalendar document
+pub fn parse(content: &str) -> Result<Vec<Event>, IcsError> {
+    // Unfold content lines continued on the next line by a leading space or tab
+    let mut lines: Vec<(usize, String)> = Vec::new();
+    for (i, l) in content.lines().enumerate() {
+        match (l.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some((_, last))) => last.push_str(rest),
+            _ => lines.push((i + 1, l.to_string())),
+        }
+    }
+
+    let mut events = Vec::new();
+    let mut properties: Option<Vec<Property>> = None;
+    let mut nested = 0;
+
+    for (line, l) in lines {
+        if l.trim().is_empty() {
+            continue;
+        }
+        let error = |reason: String| IcsError { line, reason };
+        let (name, value) = l
+            .split_once(':')
+            .ok_or_else(|| error(String::from("content line without value")))?;
+        let mut params = name.split(';');
+        let name = params.next().unwrap_or_default().to_uppercase();
+        let params: HashMap<String, String> = params
+            .filter_map(|p| p.split_once('='))
+            .map(|(k, v)| (k.to_uppercase(), v.trim_matches('"').to_string()))
+            .collect();
+
+        match (name.as_str(), value, properties.as_mut()) {
+            ("BEGIN", "VEVENT", None) => properties = Some(Vec::new()),
+            ("BEGIN", _, Some(_)) => nested += 1,
+            ("END", _, Some(_)) if nested > 0 => nested -= 1,
+            ("END", "VEVENT", Some(_)) => {
+                let props = properties.take().unwrap_or_default();
+                events
+                    .push(build_event(props).map_err(|(line, reason)| IcsError { line, reason })?);
+            }
+            (_, _, Some(props)) if nested == 0 => {
+                props.push((line, name, params, value.to_string()))
+            }
+            _ => {}
+        }
+    }
+
+    // Occurrences replaced by another event are removed from the recurring event
+    let mut overrides: HashMap<String, Vec<Time>> = HashMap::new();
+    for e in &events {
+        if let Some(r) = e.recurrence_id {
+            overrides.entry(e.uid.clone()).or_default().push(r);
+        }
+    }
+    for e in events.iter_mut() {
+        if e.rrule.is_some() && e.recurrence_id.is_none() {
+            e.exdates
+                .extend(overrides.get(&e.uid).into_iter().flatten().copied());
+        }
+    }
+
+    Ok(events)
+}
+
+type Property = (usize, String, HashMap<String, String>, String);
+
+fn build_event(properties: Vec<Property>) -> Result<Event, (usize, String)> {
+    let mut uid = String::new();
+    let mut summary = String::new();
+    let mut description = String::new();
+    let mut categories = Vec::new();
+    let mut cancelled = false;
+    let mut start = None;
+    let mut end = None;
+    let mut duration = None;
+    let mut rrule = None;
+    let mut exdates = Vec::new();
+    let mut recurrence_id = None;
+    let mut last = 0;
+
+    for (line, name, params, value) in properties {
+        last = line;
+        let err = |reason: String| (line, reason);
+        match name.as_str() {
+            "UID" => uid = value,
+            "SUMMARY" => summary = unescape(&value),
+            "DESCRIPTION" => description = unescape(&value),
+            "CATEGORIES" => categories.extend(split_list(&value)),
+            "STATUS" => cancelled = value.eq_ignore_ascii_case("CANCELLED"),
+            "DTSTART" => start = Some(Time::parse(&value, &params).map_err(err)?),
+            "DTEND" => end = Some(Time::parse(&value, &params).map_err(err)?),
+            "DURATION" => duration = Some(parse_duration(&value).map_err(err)?),
+            "RRULE" => rrule = Some(RRule::parse(&value).map_err(err)?),
+            "EXDATE" => {
+                for v in value.split(',') {
+                    exdates.push(Time::parse(v, &params).map_err(err)?);
+                }
+            }
+            "RECURRENCE-ID" => recurrence_id = Some(Time::parse(&value, &params).map_err(err)?),
+            _ => {}
+        }
+    }
+
+    let start = start.ok_or((last, format!("event {uid} without DTSTART")))?;
+    let end = match (end, duration) {
+        (Some(end), _) => end,
+        (None, Some(d)) => start.with_naive(start.naive() + d),
+        (None, None) => match start {
+            Time::Date(d) => Time::Date(d + TimeDelta::days(1)),
+            _ => start,
+        },
+    };
+
+    Ok(Event {
+        uid,
+        summary,
+        description,
+        categories,
+        cancelled,
+        start,
+        end,
+        rrule,
+        exdates,
+        recurrence_id,
+    })
+}
+
+impl Ics {
+    fn convert(&self, event: &Event, start: DateTime<Utc>, end: DateTime<Utc>) -> entries::Entry {
+        let fields = self
+            .pattern
+            .captures(&event.summary)
+            .unwrap_or_else(|| HashMap::from([(String::from("project"), event.summary.clone())]));
+        let field = |f: &str| fields.get(f).cloned().unwrap_or_default();
+
+        let mut entry = entries::Entry {
+            id: match (&event.rrule, event.recurrence_id) {
+                (_, Some(r)) => {
+                    let r = r.to_utc_in(&self.timezone);
+                    format!("{}_{}", event.uid, r.format("%Y%m%dT%H%M%SZ"))
+                }
+                (Some(_), None) => format!("{}_{}", event.uid, start.format("%Y%m%dT%H%M%SZ")),
+                (None, None) => event.uid.clone(),
+            },
+            description: match fields.get("description") {
+                Some(d) => d.clone(),
+                None if event.description.is_empty() => event.summary.clone(),
+                None => event.description.clone(),
+            },
+            billable: self.billable,
+            project: field("project"),
+            task: field("task"),
+            tags: event.categories.clone(),
+            user: String::new(),
+            running: false,
+            end,
+            start,
+        };
+
+        if self.categories != Categories::Tags && !entry.tags.is_empty() {
+            let first = entry.tags.remove(0);
+            match self.categories {
+                Categories::Project => entry.project = first,
+                _ => entry.task = first,
+            }
+        }
+
+        entry
+    }
+}
+
+#[async_trait(?Send)]
+impl Provider for Ics {
+    async fn load(
+        &mut self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<entries::Entry>, Box<dyn Error>> {
+        let content = read_to_string(&self.path)?;
+        let mut entries = Vec::new();
+
+        for event in parse(&content)? {
+            if event.cancelled || event.all_day() {
+                continue;
+            }
+            for (s, e) in event.occurrences_in(start, end, &self.timezone) {
+                entries.push(self.convert(&event, s, e));
+            }
+        }
+
+        entries.sort_by_key(|e| e.start);
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    fn event(rrule: &str) -> Event {
+        let content = format!(
+            "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:1\r\nSUMMARY:Project\r\n\
+             DTSTART:20241014T090000Z\r\nDTEND:20241014T100000Z\r\n{rrule}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n"
+        );
+        parse(&content).unwrap().remove(0)
+    }
+
+    #[test]
+    fn pattern() {
+        let p = Pattern::new("{project} - {task}");
+        let c = p.captures("Project1 - Task - 1").unwrap();
+        assert_eq!(c["project"], "Project1");
+        assert_eq!(c["task"], "Task - 1");
+        assert_eq!(p.captures("Project1"), None);
+
+        let p = Pattern::new("[{project}] {description}");
+        let c = p.captures("[Project1] fix bug").unwrap();
+        assert_eq!(c["project"], "Project1");
+        assert_eq!(c["description"], "fix bug");
+        assert_eq!(p.captures("Project1 fix bug"), None);
+    }
+
+    #[test]
+    fn invalid_options() {
+        let options = |key: &str, value: &str| {
+            HashMap::from([
+                (String::from("path"), String::from("calendar.ics")),
+                (key.to_string(), value.to_string()),
+            ])
+        };
+        assert_eq!(
+            Ics::new(options("categories", "description")).unwrap_err(),
+            OptionError::invalid("Ics", "categories", "description", "tags, project or task")
+        );
+        assert!(Ics::new(options("billable", "yes")).is_err());
+        assert!(Ics::new(options("billable", "false")).is_ok());
+        assert!(Ics::new(HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn parse_events() {
+        let content = "BEGIN:VCALENDAR\r\n\
+                       BEGIN:VEVENT\r\n\
+                       UID:1\r\n\
+                       SUMMARY:Project1 - Task1\\, first\r\n\
+                       DESCRIPTION:long\r\n  description\r\n\
+                       CATEGORIES:Tag1,Tag2\r\n\
+                       DTSTART:20241012T090000Z\r\n\
+                       DURATION:PT1H30M\r\n\
+                       BEGIN:VALARM\r\n\
+                       DESCRIPTION:alarm\r\n\
+                       END:VALARM\r\n\
+                       END:VEVENT\r\n\
+                       BEGIN:VEVENT\r\n\
+                       UID:2\r\n\
+                       DTSTART;VALUE=DATE:20241013\r\n\
+                       END:VEVENT\r\n\
+                       END:VCALENDAR\r\n";
+
+        let events = parse(content).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].summary, "Project1 - Task1, first");
+        assert_eq!(events[0].description, "long description");
+        assert_eq!(events[0].categories, vec!["Tag1", "Tag2"]);
+        assert_eq!(events[0].start.to_utc(), utc(2024, 10, 12, 9, 0));
+        assert_eq!(events[0].end.to_utc(), utc(2024, 10, 12, 10, 30));
+        assert!(!events[0].all_day());
+        assert!(events[1].all_day());
+        assert_eq!(
+            events[1].end,
+            Time::Date(NaiveDate::from_ymd_opt(2024, 10, 14).unwrap())
+        );
+    }
+
+    #[test]
+    fn time_zones() {
+        let tzid = HashMap::from([(String::from("TZID"), String::from("Europe/Paris"))]);
+        let time = Time::parse("20241014T090000", &tzid).unwrap();
+        assert_eq!(time.to_utc(), utc(2024, 10, 14, 7, 0));
+        assert!(Time::parse("20241014T090000", &HashMap::new())
+            .is_ok_and(|t| matches!(t, Time::Local(_))));
+        let unknown = HashMap::from([(String::from("TZID"), String::from("Nowhere"))]);
+        assert!(Time::parse("20241014T090000", &unknown).is_err());
+
+        // Occurrences keep their wall-clock time across daylight saving time changes
+        let content = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:1\r\nSUMMARY:Project\r\n\
+             DTSTART;TZID=Europe/Paris:20241021T090000\r\n\
+             DTEND;TZID=Europe/Paris:20241021T100000\r\n\
+             RRULE:FREQ=WEEKLY;COUNT=2\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let event = parse(content).unwrap().remove(0);
+        assert_eq!(
+            event.occurrences(utc(2024, 10, 1, 0, 0), utc(2024, 11, 1, 0, 0)),
+            vec![
+                (utc(2024, 10, 21, 7, 0), utc(2024, 10, 21, 8, 0)),
+                (utc(2024, 10, 28, 8, 0), utc(2024, 10, 28, 9, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn moved_occurrence() {
+        let content = "BEGIN:VCALENDAR\r\n\
+             BEGIN:VEVENT\r\nUID:daily\r\nSUMMARY:Project\r\n\
+             DTSTART:20241014T090000Z\r\nDTEND:20241014T100000Z\r\n\
+             RRULE:FREQ=DAILY;COUNT=3\r\nEND:VEVENT\r\n\
+             BEGIN:VEVENT\r\nUID:daily\r\nSUMMARY:Project\r\n\
+             RECURRENCE-ID:20241015T090000Z\r\n\
+             DTSTART:20241015T140000Z\r\nDTEND:20241015T150000Z\r\nEND:VEVENT\r\n\
+             END:VCALENDAR\r\n";
+        let events = parse(content).unwrap();
+        assert_eq!(
+            events[1].recurrence_id,
+            Some(Time::Utc(utc(2024, 10, 15, 9, 0).naive_utc()))
+        );
+        assert_eq!(
+            events[0].occurrences(utc(2024, 10, 1, 0, 0), utc(2024, 11, 1, 0, 0)),
+            vec![
+                (utc(2024, 10, 14, 9, 0), utc(2024, 10, 14, 10, 0)),
+                (utc(2024, 10, 16, 9, 0), utc(2024, 10, 16, 10, 0)),
+            ]
+        );
+
+        let ics = Ics::new(HashMap::from([(String::from("path"), String::new())])).unwrap();
+        let (start, end) = events[1].occurrences(utc(2024, 10, 1, 0, 0), utc(2024, 11, 1, 0, 0))[0];
+        let entry = ics.convert(&events[1], start, end);
+        assert_eq!(entry.id, "daily_20241015T090000Z");
+        assert_eq!(entry.start, utc(2024, 10, 15, 14, 0));
+    }
+
+    #[test]
+    fn parse_error() {
+        let content = "BEGIN:VEVENT\nUID:1\nDTSTART:2024-10-12\nEND:VEVENT\n";
+        assert_eq!(parse(content).err().unwrap().line, 3);
+    }
+
+    #[test]
+    fn single_occurrence() {
+        let e = event("");
+        assert_eq!(
+            e.occurrences(utc(2024, 10, 1, 0, 0), utc(2024, 10, 31, 0, 0)),
+            vec![(utc(2024, 10, 14, 9, 0), utc(2024, 10, 14, 10, 0))]
+        );
+        assert!(e
+            .occurrences(utc(2024, 11, 1, 0, 0), utc(2024, 11, 30, 0, 0))
+            .is_empty());
+    }
+
+    #[test]
+    fn daily_count() {
+        let e = event("RRULE:FREQ=DAILY;COUNT=3");
+        let starts: Vec<_> = e
+            .occurrences(utc(2024, 10, 15, 0, 0), utc(2024, 10, 31, 0, 0))
+            .into_iter()
+            .map(|(s, _)| s)
+            .collect();
+        assert_eq!(
+            starts,
+            vec![utc(2024, 10, 15, 9, 0), utc(2024, 10, 16, 9, 0)]
+        );
+    }
+
+    #[test]
+    fn weekly_by_day_until() {
+        let e = event(
+            "RRULE:FREQ=WEEKLY;BYDAY=MO,WE;UNTIL=20241023T235959Z\r\nEXDATE:20241016T090000Z",
+        );
+        let starts: Vec<_> = e
+            .occurrences(utc(2024, 10, 1, 0, 0), utc(2024, 10, 31, 0, 0))
+            .into_iter()
+            .map(|(s, _)| s)
+            .collect();
+        assert_eq!(
+            starts,
+            vec![
+                utc(2024, 10, 14, 9, 0),
+                utc(2024, 10, 21, 9, 0),
+                utc(2024, 10, 23, 9, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn monthly_interval() {
+        let e = event("RRULE:FREQ=MONTHLY;INTERVAL=2");
+        let starts: Vec<_> = e
+            .occurrences(utc(2024, 10, 1, 0, 0), utc(2025, 3, 1, 0, 0))
+            .into_iter()
+            .map(|(s, _)| s)
+            .collect();
+        assert_eq!(
+            starts,
+            vec![
+                utc(2024, 10, 14, 9, 0),
+                utc(2024, 12, 14, 9, 0),
+                utc(2025, 2, 14, 9, 0)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn load() -> Result<(), Box<dyn Error>> {
+        let path = std::env::temp_dir().join("ttm_ics_provider.ics");
+        std::fs::write(
+            &path,
+            "BEGIN:VCALENDAR\n\
+             BEGIN:VEVENT\n\
+             UID:daily\n\
+             SUMMARY:Project1 / Task1\n\
+             CATEGORIES:Tag1\n\
+             DTSTART:20241014T090000Z\n\
+             DTEND:20241014T100000Z\n\
+             RRULE:FREQ=DAILY;COUNT=2\n\
+             END:VEVENT\n\
+             BEGIN:VEVENT\n\
+             UID:cancelled\n\
+             SUMMARY:Project2\n\
+             STATUS:CANCELLED\n\
+             DTSTART:20241014T090000Z\n\
+             DTEND:20241014T100000Z\n\
+             END:VEVENT\n\
+             BEGIN:VEVENT\n\
+             UID:holiday\n\
+             SUMMARY:Holiday\n\
+             DTSTART;VALUE=DATE:20241015\n\
+             END:VEVENT\n\
+             END:VCALENDAR\n",
+        )?;
+
+        let mut ics = Ics::new(HashMap::from([
+            (String::from("path"), path.to_string_lossy().to_string()),
+            (String::from("pattern"), String::from("{project} / {task}")),
+            (String::from("categories"), String::from("project")),
+        ]))?;
+        let entries = ics
+            .load(utc(2024, 10, 1, 0, 0), utc(2024, 10, 31, 0, 0))
+            .await?;
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].id, "daily_20241015T090000Z");
+        assert_eq!(entries[1].project, "Tag1");
+        assert_eq!(entries[1].task, "Task1");
+        assert_eq!(entries[1].description, "Project1 / Task1");
+        assert!(entries[1].tags.is_empty());
+        assert!(entries[1].billable);
+        assert_eq!(entries[1].start, utc(2024, 10, 15, 9, 0));
+        assert_eq!(entries[1].end, utc(2024, 10, 15, 10, 0));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn load_in_timezone() -> Result<(), Box<dyn Error>> {
+        let path = std::env::temp_dir().join("ttm_ics_provider_timezone.ics");
+        std::fs::write(
+            &path,
+            "BEGIN:VCALENDAR\n\
+             BEGIN:VEVENT\n\
+             UID:weekly\n\
+             SUMMARY:Project1\n\
+             DTSTART:20241021T090000\n\
+             DTEND:20241021T100000\n\
+             RRULE:FREQ=WEEKLY;COUNT=2\n\
+             END:VEVENT\n\
+             BEGIN:VEVENT\n\
+             UID:weekly\n\
+             SUMMARY:Project1\n\
+             RECURRENCE-ID:20241028T090000\n\
+             DTSTART:20241028T140000\n\
+             DTEND:20241028T150000\n\
+             END:VEVENT\n\
+             END:VCALENDAR\n",
+        )?;
+
+        // Paris leaves daylight saving time between both occurrences
+        let mut ics = Ics::new(HashMap::from([
+            (String::from("path"), path.to_string_lossy().to_string()),
+            (String::from("timezone"), String::from("Europe/Paris")),
+        ]))?;
+        let entries = ics
+            .load(utc(2024, 10, 1, 0, 0), utc(2024, 11, 30, 0, 0))
+            .await?;
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].start, utc(2024, 10, 21, 7, 0));
+        assert_eq!(entries[1].id, "weekly_20241028T080000Z");
+        assert_eq!(entries[1].start, utc(2024, 10, 28, 13, 0));
+
+        let options = HashMap::from([
+            (String::from("path"), String::new()),
+            (String::from("timezone"), String::from("Mars/Olympus")),
+        ]);
+        assert!(Ics::new(options).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,255 @@
+use super::rest::{Rest, RestOptions};
+use super::{OptionError, Provider};
+use crate::entries;
+use crate::utils::local_timezone;
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Utc;
+use chrono_tz::Tz;
+use reqwest::header::HeaderMap;
+use reqwest::header::HeaderValue;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::error::Error;
+
+/// Load timesheets from a Kimai instance.
+///
+/// Kimai projects and activities become the project and the task of the entries.
+/// Authentication uses the API token as a bearer token, or the legacy
+/// `X-AUTH-USER`/`X-AUTH-TOKEN` headers when a user is given.
+/// Kimai reads the requested interval in the timezone of the user, given by `timezone`.
+#[derive(Debug)]
+pub struct Kimai {
+    base_url: String,
+    token: String,
+    user: Option<String>,
+    timezone: Tz,
+    rest: RestOptions,
+}
+
+impl Kimai {
+    pub fn new(options: HashMap<String, String>) -> Result<Kimai, OptionError> {
+        Ok(Kimai {
+            base_url: options
+                .get("base_url")
+                .ok_or_else(|| OptionError::missing("Kimai", "base_url"))?
+                .clone(),
+            token: options
+                .get("token")
+                .ok_or_else(|| OptionError::missing("Kimai", "token"))?
+                .clone(),
+            user: options.get("user").cloned(),
+            timezone: match options.get("timezone") {
+                Some(tz) => tz.parse().map_err(|_| {
+                    OptionError::invalid("Kimai", "timezone", tz, "an IANA timezone")
+                })?,
+                None => local_timezone(),
+            },
+            rest: RestOptions::new(&options).map_err(|e| e.of("Kimai"))?,
+        })
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct Named {
+    name: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct Timesheet {
+    id: u64,
+    begin: String,
+    #[serde(default)]
+    end: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default = "billable")]
+    billable: bool,
+    project: Named,
+    activity: Named,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// Timesheets are billable by default in Kimai versions without the field
+fn billable() -> bool {
+    true
+}
+
+fn parse(s: &str) -> Result<DateTime<Utc>, Box<dyn Error>> {
+    // Kimai offsets do not always contain a colon (+0200)
+    Ok(DateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%z")?.to_utc())
+}
+
+impl Timesheet {
+    /// Return None for running timesheets since they have no end yet
+    fn convert(self) -> Result<Option<entries::Entry>, Box<dyn Error>> {
+        let Some(end) = self.end else {
+            return Ok(None);
+        };
+
+        Ok(Some(entries::Entry {
+            id: self.id.to_string(),
+            description: self.description.unwrap_or_default(),
+            billable: self.billable,
+            project: self.project.name,
+            task: self.activity.name,
+            tags: self.tags,
+            user: String::new(),
+            running: false,
+            end: parse(&end)?,
+            start: parse(&self.begin)?,
+        }))
+    }
+}
+
+#[async_trait(?Send)]
+impl Provider for Kimai {
+    async fn load(
+        &mut self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<entries::Entry>, Box<dyn Error>> {
+        let mut headers = HeaderMap::new();
+        match &self.user {
+            Some(user) => {
+                headers.append("X-AUTH-USER", HeaderValue::from_str(user)?);
+                headers.append("X-AUTH-TOKEN", HeaderValue::from_str(&self.token)?);
+            }
+            None => {
+                headers.append(
+                    "Authorization",
+                    HeaderValue::from_str(&format!("Bearer {}", self.token))?,
+                );
+            }
+        }
+        let rest = Rest::new(&self.base_url, headers)?.options(self.rest);
+
+        // Dates without offset, in the timezone of the user
+        let format = |d: DateTime<Utc>| {
+            let d = d.with_timezone(&self.timezone);
+            d.format("%Y-%m-%dT%H:%M:%S").to_string()
+        };
+        let (begin, end) = (format(start), format(end));
+
+        let timesheets = rest
+            .paginate::<Vec<Timesheet>>(|page| {
+                format!("/api/timesheets?begin={begin}&end={end}&full=true&page={page}&size=100")
+            })
+            .await?;
+
+        let mut entries = Vec::with_capacity(timesheets.len());
+        for t in timesheets {
+            if let Some(e) = t.convert()? {
+                entries.push(e);
+            }
+        }
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use mockito::{Matcher, Server};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn load() -> Result<(), Box<dyn Error>> {
+        let mut server = Server::new_async().await;
+        let query = |page: &str| {
+            Matcher::AllOf(vec![
+                Matcher::UrlEncoded("begin".into(), "2024-10-01T02:00:00".into()),
+                Matcher::UrlEncoded("end".into(), "2024-10-31T01:00:00".into()),
+                Matcher::UrlEncoded("full".into(), "true".into()),
+                Matcher::UrlEncoded("page".into(), page.into()),
+            ])
+        };
+
+        let first = server
+            .mock("GET", "/api/timesheets")
+            .match_query(query("1"))
+            .match_header("X-AUTH-USER", "user")
+            .match_header("X-AUTH-TOKEN", "secret")
+            .with_header("X-Total-Pages", "2")
+            .with_body(
+                r#"[{"id": 1, "begin": "2024-10-12T11:00:00+0200", "end": "2024-10-12T12:00:00+0200",
+                     "description": "first", "billable": false, "tags": ["Tag1"],
+                     "project": {"id": 1, "name": "Project1"}, "activity": {"id": 1, "name": "Task1"}}]"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let second = server
+            .mock("GET", "/api/timesheets")
+            .match_query(query("2"))
+            .with_header("X-Total-Pages", "2")
+            .with_body(
+                r#"[{"id": 2, "begin": "2024-10-13T09:00:00+00:00", "end": null,
+                     "project": {"id": 1, "name": "Project1"}, "activity": {"id": 1, "name": "Task1"}}]"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let mut kimai = Kimai::new(HashMap::from([
+            (String::from("base_url"), server.url()),
+            (String::from("token"), String::from("secret")),
+            (String::from("user"), String::from("user")),
+            (String::from("timezone"), String::from("Europe/Paris")),
+        ]))?;
+        let entries = kimai
+            .load(
+                Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 10, 31, 0, 0, 0).unwrap(),
+            )
+            .await?;
+
+        first.assert_async().await;
+        second.assert_async().await;
+        assert_eq!(
+            entries,
+            vec![entries::Entry {
+                id: String::from("1"),
+                description: String::from("first"),
+                billable: false,
+                project: String::from("Project1"),
+                task: String::from("Task1"),
+                tags: vec![String::from("Tag1")],
+                start: Utc.with_ymd_and_hms(2024, 10, 12, 9, 0, 0).unwrap(),
+                user: String::new(),
+                running: false,
+                end: Utc.with_ymd_and_hms(2024, 10, 12, 10, 0, 0).unwrap(),
+            }]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn bearer_token() -> Result<(), Box<dyn Error>> {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/timesheets")
+            .match_query(Matcher::Any)
+            .match_header("Authorization", "Bearer secret")
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        let mut kimai = Kimai::new(HashMap::from([
+            (String::from("base_url"), server.url()),
+            (String::from("token"), String::from("secret")),
+        ]))?;
+        let entries = kimai
+            .load(
+                Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 10, 31, 0, 0, 0).unwrap(),
+            )
+            .await?;
+
+        mock.assert_async().await;
+        assert!(entries.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use super::OptionError;
+
+/// Origin of the project or the task of an entry for trackers only providing tags
+/// and optionally a project
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Source {
+    /// Field left empty
+    None,
+    /// Project given by the tracker
+    Project,
+    /// Tag at the given position, starting from 0
+    Tag(usize),
+}
+
+impl Source {
+    /// Parse `none`, `project`, `first-tag`, `second-tag` or `tag:N`
+    pub fn parse(s: &str) -> Option<Source> {
+        match s {
+            "none" => Some(Source::None),
+            "project" => Some(Source::Project),
+            "first-tag" => Some(Source::Tag(0)),
+            "second-tag" => Some(Source::Tag(1)),
+            _ => s.strip_prefix("tag:")?.parse().ok().map(Source::Tag),
+        }
+    }
+}
+
+/// Rule used to fill the project, the task and the tags of an entry.
+/// Tags used as project or task are not kept as tags.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Mapping {
+    pub project: Source,
+    pub task: Source,
+}
+
+impl Mapping {
+    /// Build the mapping from the `project` and `task` options of the provider
+    pub fn new(
+        provider: &str,
+        options: &HashMap<String, String>,
+        project: Source,
+        task: Source,
+    ) -> Result<Mapping, OptionError> {
+        let get = |key: &str, default: Source| match options.get(key) {
+            Some(s) => Source::parse(s).ok_or_else(|| {
+                let expected = "none, project, first-tag, second-tag or tag:N";
+                OptionError::invalid(provider, key, s, expected)
+            }),
+            None => Ok(default),
+        };
+
+        Ok(Mapping {
+            project: get("project", project)?,
+            task: get("task", task)?,
+        })
+    }
+
+    /// Return the project, the task and the remaining tags
+    pub fn apply(&self, project: &str, tags: Vec<String>) -> (String, String, Vec<String>) {
+        let get = |s: Source| match s {
+            Source::None => String::new(),
+            Source::Project => project.to_string(),
+            Source::Tag(i) => tags.get(i).cloned().unwrap_or_default(),
+        };
+
+        let p = get(self.project);
+        let t = get(self.task);
+        let tags = tags
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| Source::Tag(*i) != self.project && Source::Tag(*i) != self.task)
+            .map(|(_, t)| t)
+            .collect();
+
+        (p, t, tags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(Source::parse("none"), Some(Source::None));
+        assert_eq!(Source::parse("project"), Some(Source::Project));
+        assert_eq!(Source::parse("first-tag"), Some(Source::Tag(0)));
+        assert_eq!(Source::parse("tag:3"), Some(Source::Tag(3)));
+        assert_eq!(Source::parse("tag:"), None);
+        assert_eq!(Source::parse("other"), None);
+    }
+
+    #[test]
+    fn apply() {
+        let tags = vec![String::from("a"), String::from("b"), String::from("c")];
+        let mapping = Mapping {
+            project: Source::Tag(0),
+            task: Source::Tag(2),
+        };
+        assert_eq!(
+            mapping.apply("", tags.clone()),
+            (
+                String::from("a"),
+                String::from("c"),
+                vec![String::from("b")]
+            )
+        );
+
+        let mapping = Mapping {
+            project: Source::Project,
+            task: Source::Tag(5),
+        };
+        assert_eq!(
+            mapping.apply("p", tags.clone()),
+            (String::from("p"), String::new(), tags)
+        );
+    }
+
+    #[test]
+    fn from_options() {
+        let options = HashMap::from([(String::from("task"), String::from("first-tag"))]);
+        let mapping = Mapping::new("Watson", &options, Source::Project, Source::None).unwrap();
+        assert_eq!(mapping.project, Source::Project);
+        assert_eq!(mapping.task, Source::Tag(0));
+
+        let options = HashMap::from([(String::from("project"), String::from("tag"))]);
+        let error = Mapping::new("Watson", &options, Source::Project, Source::None).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Watson provider option project should be none, project, first-tag, second-tag or tag:N, not tag"
+        );
+    }
+}
@@ -0,0 +1,252 @@
+use super::mapping::{Mapping, Source};
+use super::{OptionError, Provider};
+use crate::entries;
+use async_trait::async_trait;
+use chrono::{DateTime, Months, NaiveDateTime, Utc};
+use std::collections::HashMap;
+use std::env;
+use std::error::Error;
+use std::fs::{read_dir, read_to_string};
+use std::path::PathBuf;
+
+/// Load intervals from the Timewarrior database (`~/.timewarrior/data/*.data`).
+///
+/// Timewarrior only records tags: by default the first tag is the project and the
+/// others are kept as tags. Annotations become the description of the entries.
+/// Open intervals (running tracking) are ignored.
+#[derive(Debug)]
+pub struct Timewarrior {
+    path: PathBuf,
+    mapping: Mapping,
+    billable: bool,
+}
+
+impl Timewarrior {
+    pub fn new(options: HashMap<String, String>) -> Result<Timewarrior, OptionError> {
+        let path = match options.get("path") {
+            Some(p) => PathBuf::from(p),
+            None => match env::var("TIMEWARRIORDB") {
+                Ok(db) => PathBuf::from(db).join("data"),
+                Err(_) => PathBuf::from(
+                    env::var("HOME").map_err(|_| OptionError::missing("Timewarrior", "path"))?,
+                )
+                .join(".timewarrior")
+                .join("data"),
+            },
+        };
+
+        Ok(Timewarrior {
+            path,
+            mapping: Mapping::new("Timewarrior", &options, Source::Tag(0), Source::None)?,
+            billable: match options.get("billable") {
+                Some(b) => b
+                    .parse()
+                    .map_err(|_| OptionError::invalid("Timewarrior", "billable", b, "a boolean"))?,
+                None => true,
+            },
+        })
+    }
+}
+
+/// Split a line into words, keeping quoted strings together
+fn tokenize(s: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => quoted = !quoted,
+            '\\' if quoted => {
+                if let Some(c) = chars.next() {
+                    current.push(c);
+                }
+            }
+            ' ' if !quoted => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            _ => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+fn parse_time(s: &str) -> Result<DateTime<Utc>, Box<dyn Error>> {
+    Ok(NaiveDateTime::parse_from_str(s, "%Y%m%dT%H%M%SZ")?.and_utc())
+}
+
+/// Interval of the Timewarrior database: `inc <start> [- <end>] [# <tags> [# <annotation>]]`
+#[derive(Debug, PartialEq)]
+struct Interval {
+    start: DateTime<Utc>,
+    end: Option<DateTime<Utc>>,
+    tags: Vec<String>,
+    annotation: String,
+}
+
+impl Interval {
+    fn parse(line: &str) -> Result<Option<Interval>, Box<dyn Error>> {
+        let Some(line) = line.trim().strip_prefix("inc ") else {
+            return Ok(None);
+        };
+        let (times, rest) = match line.split_once(" # ") {
+            Some((times, rest)) => (times, rest),
+            None => (line.trim_end_matches(" #"), ""),
+        };
+
+        let mut times = times.split(" - ");
+        let start = parse_time(times.next().unwrap_or_default().trim())?;
+        let end = times.next().map(|e| parse_time(e.trim())).transpose()?;
+
+        let mut tags = Vec::new();
+        let mut annotation = Vec::new();
+        let mut in_annotation = false;
+        for token in tokenize(rest) {
+            match (token.as_str(), in_annotation) {
+                ("#", false) => in_annotation = true,
+                (_, false) => tags.push(token),
+                (_, true) => annotation.push(token),
+            }
+        }
+
+        Ok(Some(Interval {
+            start,
+            end,
+            tags,
+            annotation: annotation.join(" "),
+        }))
+    }
+}
+
+#[async_trait(?Send)]
+impl Provider for Timewarrior {
+    async fn load(
+        &mut self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<entries::Entry>, Box<dyn Error>> {
+        // Intervals are stored in the file of the month they start, so an interval
+        // overlapping the start may be in the previous file
+        let first = (start - Months::new(1)).format("%Y-%m").to_string();
+        let last = end.format("%Y-%m").to_string();
+
+        let mut files: Vec<PathBuf> = read_dir(&self.path)?
+            .filter_map(|f| f.ok().map(|f| f.path()))
+            .filter(|f| {
+                // Data files are named after the month they contain: YYYY-MM.data
+                f.extension().is_some_and(|e| e == "data")
+                    && f.file_stem()
+                        .map(|s| s.to_string_lossy().to_string())
+                        .is_some_and(|s| first <= s && s <= last)
+            })
+            .collect();
+        files.sort();
+
+        let mut entries = Vec::new();
+        for f in files {
+            for line in read_to_string(&f)?.lines() {
+                let Some(interval) = Interval::parse(line)? else {
+                    continue;
+                };
+                let Some(e) = interval.end else {
+                    continue;
+                };
+                if e <= start || interval.start >= end {
+                    continue;
+                }
+
+                let (project, task, tags) = self.mapping.apply("", interval.tags);
+                entries.push(entries::Entry {
+                    id: interval.start.format("%Y%m%dT%H%M%SZ").to_string(),
+                    description: interval.annotation,
+                    billable: self.billable,
+                    project,
+                    task,
+                    tags,
+                    user: String::new(),
+                    running: false,
+                    end: e,
+                    start: interval.start,
+                });
+            }
+        }
+
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn parse_interval() {
+        let i = Interval::parse(
+            r#"inc 20241012T090000Z - 20241012T100000Z # Project1 "Task 1" Tag1 # "fix \"bug\"""#,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            i.start,
+            Utc.with_ymd_and_hms(2024, 10, 12, 9, 0, 0).unwrap()
+        );
+        assert_eq!(
+            i.end,
+            Some(Utc.with_ymd_and_hms(2024, 10, 12, 10, 0, 0).unwrap())
+        );
+        assert_eq!(i.tags, vec!["Project1", "Task 1", "Tag1"]);
+        assert_eq!(i.annotation, "fix \"bug\"");
+
+        let i = Interval::parse("inc 20241012T090000Z").unwrap().unwrap();
+        assert_eq!(i.end, None);
+        assert!(i.tags.is_empty());
+
+        assert_eq!(Interval::parse("").unwrap(), None);
+        assert!(Interval::parse("inc 2024-10-12 - 2024-10-13").is_err());
+    }
+
+    #[tokio::test]
+    async fn load() -> Result<(), Box<dyn Error>> {
+        let dir = env::temp_dir().join("ttm_timewarrior");
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(
+            dir.join("2024-09.data"),
+            "inc 20240930T090000Z - 20240930T100000Z # Project1\n\
+             inc 20240930T230000Z - 20241001T010000Z # Project3\n",
+        )?;
+        std::fs::write(
+            dir.join("2024-10.data"),
+            "inc 20241012T090000Z - 20241012T100000Z # Project1 Task1 Tag1\n\
+             inc 20241013T090000Z - 20241013T100000Z\n\
+             inc 20241014T090000Z # Project2\n",
+        )?;
+
+        let mut tw = Timewarrior::new(HashMap::from([
+            (String::from("path"), dir.to_string_lossy().to_string()),
+            (String::from("task"), String::from("second-tag")),
+        ]))?;
+        let entries = tw
+            .load(
+                Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 10, 31, 0, 0, 0).unwrap(),
+            )
+            .await?;
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].project, "Project3");
+        assert_eq!(entries[1].project, "Project1");
+        assert_eq!(entries[1].task, "Task1");
+        assert_eq!(entries[1].tags, vec!["Tag1"]);
+        assert!(entries[1].billable);
+        assert_eq!(entries[2].project, "");
+        Ok(())
+    }
+}
@@ -0,0 +1,344 @@
+use super::rest::{Rest, RestOptions};
+use super::{OptionError, Provider};
+use crate::entries;
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Utc;
+use reqwest::header::HeaderMap;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::error::Error;
+
+const BASE_URL: &str = "https://api.track.toggl.com/api/v9";
+
+#[derive(Debug)]
+pub struct Toggl {
+    token: String,
+    base_url: String,
+    rest: RestOptions,
+}
+
+impl Toggl {
+    pub fn new(options: HashMap<String, String>) -> Result<Toggl, OptionError> {
+        Ok(Toggl {
+            token: options
+                .get("token")
+                .ok_or_else(|| OptionError::missing("Toggl", "token"))?
+                .clone(),
+            base_url: options
+                .get("base_url")
+                .cloned()
+                .unwrap_or(String::from(BASE_URL)),
+            rest: RestOptions::new(&options).map_err(|e| e.of("Toggl"))?,
+        })
+    }
+}
+
+async fn fetch<T: DeserializeOwned + Default>(
+    rest: &Rest,
+    path: &str,
+) -> Result<T, Box<dyn Error>> {
+    // Toggl answers `null` instead of an empty list when nothing matches
+    Ok(rest.fetch::<Option<T>>(path).await?.unwrap_or_default())
+}
+
+#[derive(Deserialize, Debug)]
+struct Project {
+    id: u64,
+    name: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct Task {
+    id: u64,
+    name: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct Tag {
+    id: u64,
+    name: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct Entry {
+    id: u64,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    billable: bool,
+    #[serde(default)]
+    project_id: Option<u64>,
+    #[serde(default)]
+    task_id: Option<u64>,
+    #[serde(default)]
+    tags: Option<Vec<String>>,
+    #[serde(default)]
+    tag_ids: Option<Vec<u64>>,
+    start: String,
+    #[serde(default)]
+    stop: Option<String>,
+}
+
+/// Names of the projects, tasks and tags referenced by identifier in time entries
+#[derive(Debug, Default)]
+struct Names {
+    projects: HashMap<u64, String>,
+    tasks: HashMap<u64, String>,
+    tags: HashMap<u64, String>,
+}
+
+impl Entry {
+    /// Convert a Toggl time entry into an [`entries::Entry`].
+    /// Return None for running time entries since they have no end yet.
+    fn convert(&self, names: &Names) -> Result<Option<entries::Entry>, Box<dyn Error>> {
+        let stop = match &self.stop {
+            Some(stop) => stop,
+            None => return Ok(None),
+        };
+
+        let tags = match &self.tag_ids {
+            Some(ids) if !ids.is_empty() => ids
+                .iter()
+                .filter_map(|id| names.tags.get(id).cloned())
+                .collect(),
+            _ => self.tags.clone().unwrap_or_default(),
+        };
+
+        Ok(Some(entries::Entry {
+            id: self.id.to_string(),
+            description: self.description.clone().unwrap_or_default(),
+            billable: self.billable,
+            project: self
+                .project_id
+                .and_then(|id| names.projects.get(&id).cloned())
+                .unwrap_or_default(),
+            task: self
+                .task_id
+                .and_then(|id| names.tasks.get(&id).cloned())
+                .unwrap_or_default(),
+            tags,
+            user: String::new(),
+            running: false,
+            end: DateTime::parse_from_rfc3339(stop)?.to_utc(),
+            start: DateTime::parse_from_rfc3339(&self.start)?.to_utc(),
+        }))
+    }
+}
+
+#[async_trait(?Send)]
+impl Provider for Toggl {
+    async fn load(
+        &mut self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<entries::Entry>, Box<dyn Error>> {
+        let rest = Rest::new(&self.base_url, HeaderMap::new())?
+            .basic_auth(&self.token, "api_token")
+            .options(self.rest);
+
+        let projects: Vec<Project> = fetch(&rest, "/me/projects").await?;
+        let tasks: Vec<Task> = fetch(&rest, "/me/tasks").await?;
+        let tags: Vec<Tag> = fetch(&rest, "/me/tags").await?;
+        let names = Names {
+            projects: projects.into_iter().map(|p| (p.id, p.name)).collect(),
+            tasks: tasks.into_iter().map(|t| (t.id, t.name)).collect(),
+            tags: tags.into_iter().map(|t| (t.id, t.name)).collect(),
+        };
+
+        let format = "%Y-%m-%dT%H:%M:%SZ";
+        let start_date = start.format(format).to_string();
+
+        let mut entries: Vec<entries::Entry> = Vec::new();
+        let mut seen: HashSet<u64> = HashSet::new();
+
+        // Time entries are returned from the most recent to the oldest.
+        // Pages are retrieved by moving the end of the requested interval
+        // to the start of the oldest entry received so far.
+        let mut cursor = end;
+        loop {
+            let end_date = cursor.format(format).to_string();
+            let res: Vec<Entry> = fetch(
+                &rest,
+                &format!("/me/time_entries?start_date={start_date}&end_date={end_date}"),
+            )
+            .await?;
+
+            let mut new = false;
+            for e in res {
+                if !seen.insert(e.id) {
+                    continue;
+                }
+                new = true;
+                let s = DateTime::parse_from_rfc3339(&e.start)?.to_utc();
+                if s < cursor {
+                    cursor = s;
+                }
+                if let Some(e) = e.convert(&names)? {
+                    entries.push(e);
+                }
+            }
+
+            if !new || cursor <= start {
+                break;
+            }
+        }
+
+        entries.sort_by_key(|e| e.start);
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use mockito::{Matcher, Server};
+
+    use super::*;
+
+    fn options(server: &Server) -> HashMap<String, String> {
+        HashMap::from([
+            (String::from("token"), String::from("secret")),
+            (String::from("base_url"), server.url()),
+        ])
+    }
+
+    #[test]
+    fn missing_token() {
+        assert_eq!(
+            Toggl::new(HashMap::new()).unwrap_err(),
+            OptionError::missing("Toggl", "token")
+        );
+    }
+
+    #[tokio::test]
+    async fn load() -> Result<(), Box<dyn Error>> {
+        let mut server = Server::new_async().await;
+        let auth = Matcher::Exact(String::from("Basic c2VjcmV0OmFwaV90b2tlbg=="));
+
+        server
+            .mock("GET", "/me/projects")
+            .match_header("authorization", auth.clone())
+            .with_body(r#"[{"id": 1, "name": "Project1"}, {"id": 2, "name": "Project2"}]"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/me/tasks")
+            .with_body(r#"[{"id": 10, "name": "Task1", "project_id": 1}]"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/me/tags")
+            .with_body(r#"[{"id": 100, "name": "Ignore"}]"#)
+            .create_async()
+            .await;
+
+        let first = server
+            .mock("GET", "/me/time_entries")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("start_date".into(), "2024-10-01T00:00:00Z".into()),
+                Matcher::UrlEncoded("end_date".into(), "2024-10-31T00:00:00Z".into()),
+            ]))
+            .with_body(
+                r#"[
+                    {"id": 3, "description": "running", "billable": true, "project_id": 2,
+                     "start": "2024-10-14T09:00:00+00:00", "stop": null, "duration": -1},
+                    {"id": 2, "description": "second", "billable": false, "project_id": 2,
+                     "tags": ["Ignore"], "tag_ids": [100],
+                     "start": "2024-10-13T09:00:00+00:00", "stop": "2024-10-13T11:00:00+00:00"}
+                ]"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let second = server
+            .mock("GET", "/me/time_entries")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("start_date".into(), "2024-10-01T00:00:00Z".into()),
+                Matcher::UrlEncoded("end_date".into(), "2024-10-13T09:00:00Z".into()),
+            ]))
+            .with_body(
+                r#"[
+                    {"id": 1, "description": "first", "billable": true, "project_id": 1, "task_id": 10,
+                     "start": "2024-10-12T09:00:00+02:00", "stop": "2024-10-12T10:00:00+02:00"}
+                ]"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let last = server
+            .mock("GET", "/me/time_entries")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("start_date".into(), "2024-10-01T00:00:00Z".into()),
+                Matcher::UrlEncoded("end_date".into(), "2024-10-12T07:00:00Z".into()),
+            ]))
+            .with_body("null")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let mut toggl = Toggl::new(options(&server))?;
+        let entries = toggl
+            .load(
+                Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 10, 31, 0, 0, 0).unwrap(),
+            )
+            .await?;
+
+        first.assert_async().await;
+        second.assert_async().await;
+        last.assert_async().await;
+
+        assert_eq!(
+            entries,
+            vec![
+                entries::Entry {
+                    id: String::from("1"),
+                    description: String::from("first"),
+                    billable: true,
+                    project: String::from("Project1"),
+                    task: String::from("Task1"),
+                    tags: vec![],
+                    start: Utc.with_ymd_and_hms(2024, 10, 12, 7, 0, 0).unwrap(),
+                    user: String::new(),
+                    running: false,
+                    end: Utc.with_ymd_and_hms(2024, 10, 12, 8, 0, 0).unwrap(),
+                },
+                entries::Entry {
+                    id: String::from("2"),
+                    description: String::from("second"),
+                    billable: false,
+                    project: String::from("Project2"),
+                    task: String::new(),
+                    tags: vec![String::from("Ignore")],
+                    start: Utc.with_ymd_and_hms(2024, 10, 13, 9, 0, 0).unwrap(),
+                    user: String::new(),
+                    running: false,
+                    end: Utc.with_ymd_and_hms(2024, 10, 13, 11, 0, 0).unwrap(),
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unauthorized() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/me/projects")
+            .with_status(403)
+            .create_async()
+            .await;
+
+        let mut toggl = Toggl::new(options(&server)).unwrap();
+        let res = toggl
+            .load(
+                Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 10, 31, 0, 0, 0).unwrap(),
+            )
+            .await;
+        assert!(res.is_err());
+    }
+}
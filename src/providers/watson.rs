@@ -0,0 +1,156 @@
+use super::mapping::{Mapping, Source};
+use super::{OptionError, Provider};
+use crate::entries;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::env;
+use std::error::Error;
+use std::fs::read_to_string;
+use std::path::PathBuf;
+
+/// Load frames from the Watson database (`~/.config/watson/frames`).
+///
+/// By default, the Watson project is the project of the entries and all the tags
+/// are kept as tags.
+#[derive(Debug)]
+pub struct Watson {
+    path: PathBuf,
+    mapping: Mapping,
+    billable: bool,
+}
+
+impl Watson {
+    pub fn new(options: HashMap<String, String>) -> Result<Watson, OptionError> {
+        let path = match options.get("path") {
+            Some(p) => PathBuf::from(p),
+            None => match env::var("WATSON_DIR") {
+                Ok(dir) => PathBuf::from(dir).join("frames"),
+                Err(_) => PathBuf::from(
+                    env::var("HOME").map_err(|_| OptionError::missing("Watson", "path"))?,
+                )
+                .join(".config")
+                .join("watson")
+                .join("frames"),
+            },
+        };
+
+        Ok(Watson {
+            path,
+            mapping: Mapping::new("Watson", &options, Source::Project, Source::None)?,
+            billable: match options.get("billable") {
+                Some(b) => b
+                    .parse()
+                    .map_err(|_| OptionError::invalid("Watson", "billable", b, "a boolean"))?,
+                None => true,
+            },
+        })
+    }
+}
+
+/// Frame stored by Watson: `[start, stop, project, id, tags, updated_at]`
+type Frame = (i64, i64, String, String, Vec<String>, i64);
+
+fn timestamp(t: i64) -> Result<DateTime<Utc>, String> {
+    DateTime::from_timestamp(t, 0).ok_or_else(|| format!("invalid timestamp {t}"))
+}
+
+#[async_trait(?Send)]
+impl Provider for Watson {
+    async fn load(
+        &mut self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<entries::Entry>, Box<dyn Error>> {
+        let frames: Vec<Frame> = serde_json::from_str(&read_to_string(&self.path)?)?;
+        let mut entries = Vec::new();
+
+        for (s, e, project, id, tags, _) in frames {
+            let s = timestamp(s)?;
+            let e = timestamp(e)?;
+            if e <= start || s >= end {
+                continue;
+            }
+
+            let (project, task, tags) = self.mapping.apply(&project, tags);
+            entries.push(entries::Entry {
+                id,
+                description: String::new(),
+                billable: self.billable,
+                project,
+                task,
+                tags,
+                user: String::new(),
+                running: false,
+                end: e,
+                start: s,
+            });
+        }
+
+        entries.sort_by_key(|e| e.start);
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn load() -> Result<(), Box<dyn Error>> {
+        let path = env::temp_dir().join("ttm_watson_frames");
+        std::fs::write(
+            &path,
+            r#"[
+                [1727686800, 1727690400, "Project1", "a", [], 1727690400],
+                [1728723600, 1728727200, "Project1", "b", ["Task1", "Tag1"], 1728727200],
+                [1728810000, 1728813600, "Project2", "c", ["Task2"], 1728813600]
+            ]"#,
+        )?;
+
+        let mut watson = Watson::new(HashMap::from([
+            (String::from("path"), path.to_string_lossy().to_string()),
+            (String::from("task"), String::from("first-tag")),
+            (String::from("billable"), String::from("false")),
+        ]))?;
+        let entries = watson
+            .load(
+                Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 10, 31, 0, 0, 0).unwrap(),
+            )
+            .await?;
+
+        assert_eq!(
+            entries,
+            vec![
+                entries::Entry {
+                    id: String::from("b"),
+                    description: String::new(),
+                    billable: false,
+                    project: String::from("Project1"),
+                    task: String::from("Task1"),
+                    tags: vec![String::from("Tag1")],
+                    start: Utc.with_ymd_and_hms(2024, 10, 12, 9, 0, 0).unwrap(),
+                    user: String::new(),
+                    running: false,
+                    end: Utc.with_ymd_and_hms(2024, 10, 12, 10, 0, 0).unwrap(),
+                },
+                entries::Entry {
+                    id: String::from("c"),
+                    description: String::new(),
+                    billable: false,
+                    project: String::from("Project2"),
+                    task: String::from("Task2"),
+                    tags: vec![],
+                    start: Utc.with_ymd_and_hms(2024, 10, 13, 9, 0, 0).unwrap(),
+                    user: String::new(),
+                    running: false,
+                    end: Utc.with_ymd_and_hms(2024, 10, 13, 10, 0, 0).unwrap(),
+                },
+            ]
+        );
+        Ok(())
+    }
+}
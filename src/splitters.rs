@@ -0,0 +1,113 @@
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+
+use crate::{args::Args, entries::Entry, errors::InvalidTimezone, utils::midnight};
+
+pub struct SplitParam {
+    timezone: Tz,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+}
+
+impl SplitParam {
+    pub fn build(args: &Args) -> Result<SplitParam, InvalidTimezone> {
+        Ok(SplitParam {
+            timezone: args.timezone()?,
+            start: args.start(),
+            end: args.end(),
+        })
+    }
+}
+
+/// Clip the entry to the start..end window and split it at midnight in the timezone,
+/// so that each part is counted on its own day.
+/// Entries outside of the window give no part.
+pub fn split_entry(e: Entry, p: &SplitParam) -> Vec<Entry> {
+    let end = e.end.min(p.end);
+    let mut start = e.start.max(p.start);
+    let mut parts = Vec::new();
+
+    while start < end {
+        let day = start.with_timezone(&p.timezone).date_naive();
+        let next = midnight(&p.timezone, day.succ_opt().unwrap());
+        let part = Entry {
+            start,
+            end: next.min(end),
+            ..e.clone()
+        };
+        start = part.end;
+        parts.push(part);
+    }
+
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeDelta, TimeZone};
+
+    use super::*;
+
+    fn param(timezone: Tz) -> SplitParam {
+        SplitParam {
+            timezone,
+            start: Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap(),
+            end: Utc.with_ymd_and_hms(2024, 11, 1, 0, 0, 0).unwrap(),
+        }
+    }
+
+    fn entry(start: DateTime<Utc>, hours: i64) -> Entry {
+        Entry {
+            id: String::from("1"),
+            project: String::from("Project1"),
+            start,
+            end: start + TimeDelta::hours(hours),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn same_day() {
+        let e = entry(Utc.with_ymd_and_hms(2024, 10, 12, 9, 0, 0).unwrap(), 2);
+        assert_eq!(split_entry(e.clone(), &param(Tz::UTC)), vec![e]);
+    }
+
+    #[test]
+    fn across_midnight() {
+        // 22:00 to 02:00 in Paris
+        let e = entry(Utc.with_ymd_and_hms(2024, 10, 12, 20, 0, 0).unwrap(), 4);
+        let parts = split_entry(e, &param(Tz::Europe__Paris));
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].duration(), TimeDelta::hours(2));
+        assert_eq!(parts[1].duration(), TimeDelta::hours(2));
+        assert_eq!(
+            parts[1].start,
+            Utc.with_ymd_and_hms(2024, 10, 12, 22, 0, 0).unwrap()
+        );
+        assert_eq!(parts[1].project, "Project1");
+
+        // Same entry, within a single day in UTC
+        let e = entry(Utc.with_ymd_and_hms(2024, 10, 12, 20, 0, 0).unwrap(), 4);
+        assert_eq!(split_entry(e, &param(Tz::UTC)).len(), 1);
+    }
+
+    #[test]
+    fn several_days() {
+        let e = entry(Utc.with_ymd_and_hms(2024, 10, 12, 12, 0, 0).unwrap(), 60);
+        let parts = split_entry(e, &param(Tz::UTC));
+        let hours: Vec<i64> = parts.iter().map(|p| p.duration().num_hours()).collect();
+        assert_eq!(hours, vec![12, 24, 24]);
+    }
+
+    #[test]
+    fn clipped() {
+        let e = entry(Utc.with_ymd_and_hms(2024, 9, 30, 20, 0, 0).unwrap(), 6);
+        let parts = split_entry(e, &param(Tz::UTC));
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].start, param(Tz::UTC).start);
+        assert_eq!(parts[0].duration(), TimeDelta::hours(2));
+
+        let e = entry(Utc.with_ymd_and_hms(2024, 11, 2, 9, 0, 0).unwrap(), 1);
+        assert!(split_entry(e, &param(Tz::UTC)).is_empty());
+    }
+}
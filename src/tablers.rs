@@ -1,14 +1,116 @@
+pub mod aggregate;
+pub mod hours;
 pub mod proportional;
+pub mod target;
 
 use std::{
+    cmp::Ordering,
     collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    error::Error,
     fmt::Display,
+    fs::read_to_string,
 };
 
-use chrono::{DateTime, Datelike, TimeZone, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, TimeDelta, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
 
-use crate::entries::Entry;
+use crate::{
+    args::Args,
+    entries::Entry,
+    errors::{CapacityConflict, InvalidCapacity, InvalidGranularity, InvalidTarget},
+    utils::{split___, split_eq},
+};
+
+/// Length of the columns of a table
+#[derive(
+    ValueEnum, Serialize, Deserialize, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum Period {
+    #[default]
+    Day,
+    /// ISO week, starting on monday
+    Week,
+    Month,
+}
+
+impl Period {
+    /// First day of the period containing the day
+    pub fn start(&self, day: NaiveDate) -> NaiveDate {
+        match self {
+            Period::Day => day,
+            Period::Week => day.week(Weekday::Mon).first_day(),
+            Period::Month => day.with_day(1).unwrap(),
+        }
+    }
+
+    /// Header of a column: day of the month, ISO week like 2024-W42 or month like 2024-10
+    pub fn label(&self, col: &DateTime<Utc>) -> String {
+        match self {
+            Period::Day => col.day().to_string(),
+            Period::Week => {
+                let week = col.iso_week();
+                format!("{}-W{:02}", week.year(), week.week())
+            }
+            Period::Month => col.format("%Y-%m").to_string(),
+        }
+    }
+
+    /// Header of a group of columns: its month for days, its year otherwise
+    pub fn group_label(&self, group: &DateTime<Utc>) -> String {
+        match self {
+            Period::Day => group.format("%Y %m").to_string(),
+            Period::Week | Period::Month => group.format("%Y").to_string(),
+        }
+    }
+}
+
+/// Cells of a table, summed into the totals of the rows and columns
+pub trait Cell: Clone + Default + Display {
+    type Total: Display + PartialOrd;
+    /// Total of cells taken from columns of the period
+    fn total<'a>(cells: impl Iterator<Item = &'a Self>, period: Period) -> Self::Total
+    where
+        Self: 'a;
+}
+
+/// Percents of columns summed into columns, displayed as 3.75 days, weeks or months
+#[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Periods(pub f64, pub Period);
+
+impl Display for Periods {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let unit = match self.1 {
+            Period::Day => "days",
+            Period::Week => "weeks",
+            Period::Month => "months",
+        };
+        write!(f, "{:.2} {unit}", self.0)
+    }
+}
+
+impl Cell for u8 {
+    type Total = Periods;
+
+    fn total<'a>(cells: impl Iterator<Item = &'a u8>, period: Period) -> Periods {
+        Periods(cells.map(|c| f64::from(*c)).sum::<f64>() / 100.0, period)
+    }
+}
+
+impl Cell for TimeDelta {
+    type Total = TimeDelta;
+
+    fn total<'a>(cells: impl Iterator<Item = &'a TimeDelta>, _: Period) -> TimeDelta {
+        cells.sum()
+    }
+}
 
+/// Columns of a table are days, as midnight UTC of the calendar date in the timezone
+/// used to compute them, so that they are grouped and displayed by their date.
+/// When a table has a longer period, each column is the first day of its period.
+/// Columns are iterated in chronological order, rows in the order of the table.
 pub trait Table {
     type RowIter<'a>: Iterator<Item = &'a String>
     where
@@ -19,11 +121,23 @@ pub trait Table {
     type Item<'a>
     where
         Self: 'a;
+    type Total: Display;
 
     fn row_headers(&self) -> Self::RowIter<'_>;
     fn col_headers(&self) -> Self::ColIter<'_>;
     fn get(&self, row: String, col: DateTime<Utc>) -> Self::Item<'_>;
 
+    /// Total of a row over the columns
+    fn row_total<'a>(
+        &self,
+        row: &str,
+        cols: impl IntoIterator<Item = &'a DateTime<Utc>>,
+    ) -> Self::Total;
+    /// Total of a column over every row
+    fn col_total(&self, col: &DateTime<Utc>) -> Self::Total;
+    /// Total of every row over the columns
+    fn total<'a>(&self, cols: impl IntoIterator<Item = &'a DateTime<Utc>>) -> Self::Total;
+
     fn group_by_month(&self) -> BTreeMap<DateTime<Utc>, BTreeSet<DateTime<Utc>>> {
         let mut groups: BTreeMap<DateTime<Utc>, BTreeSet<DateTime<Utc>>> = BTreeMap::new();
 
@@ -42,22 +156,68 @@ pub trait Table {
 
         groups
     }
+
+    fn period(&self) -> Period {
+        Period::Day
+    }
+
+    /// Columns displayed together: days grouped by month, weeks and months by year
+    fn groups(&self) -> BTreeMap<DateTime<Utc>, BTreeSet<DateTime<Utc>>> {
+        if self.period() == Period::Day {
+            return self.group_by_month();
+        }
+
+        let mut groups: BTreeMap<DateTime<Utc>, BTreeSet<DateTime<Utc>>> = BTreeMap::new();
+        for h in self.col_headers() {
+            // Weeks belong to their ISO year, like their label
+            let year = match self.period() {
+                Period::Week => h.iso_week().year(),
+                _ => h.year(),
+            };
+            let y = Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0).unwrap();
+            groups.entry(y).or_default().insert(*h);
+        }
+        groups
+    }
+}
+
+/// Order of the rows of a table
+#[derive(ValueEnum, Serialize, Deserialize, Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum RowOrder {
+    #[default]
+    Alphabetical,
+    /// Largest total over every column first, ties in alphabetical order
+    Total,
+    /// Rows given by the user first, in their order, then the others in alphabetical order.
+    /// A project stands for all its tasks.
+    Custom,
 }
 
 #[derive(Default, Debug)]
 pub struct MyTable<T> {
-    row_headers: HashSet<String>,
-    col_headers: HashSet<DateTime<Utc>>,
-    content: HashMap<(String, DateTime<Utc>), T>,
+    row_headers: BTreeSet<String>,
+    col_headers: BTreeSet<DateTime<Utc>>,
+    content: BTreeMap<(String, DateTime<Utc>), T>,
+    period: Period,
+    row_order: RowOrder,
+    /// Rows listed first by the custom order
+    custom_order: Vec<String>,
+    /// Position of the rows computed by the row order, the rows without one coming
+    /// last in alphabetical order
+    ranks: HashMap<String, usize>,
 }
 
-impl<T: Clone + Default> Table for MyTable<T> {
-    type RowIter<'a> = std::collections::hash_set::Iter<'a, String> where T: 'a;
-    type ColIter<'a> = std::collections::hash_set::Iter<'a, DateTime<Utc>> where T: 'a;
+impl<T: Cell> Table for MyTable<T> {
+    type RowIter<'a> = std::vec::IntoIter<&'a String> where T: 'a;
+    type ColIter<'a> = std::collections::btree_set::Iter<'a, DateTime<Utc>> where T: 'a;
     type Item<'a> = T where Self: 'a;
+    type Total = T::Total;
 
     fn row_headers(&self) -> Self::RowIter<'_> {
-        self.row_headers.iter()
+        let mut rows: Vec<&String> = self.row_headers.iter().collect();
+        rows.sort_by_key(|r| self.ranks.get(*r).copied().unwrap_or(usize::MAX));
+        rows.into_iter()
     }
 
     fn col_headers(&self) -> Self::ColIter<'_> {
@@ -70,6 +230,44 @@ impl<T: Clone + Default> Table for MyTable<T> {
             None => Default::default(),
         }
     }
+
+    fn row_total<'a>(
+        &self,
+        row: &str,
+        cols: impl IntoIterator<Item = &'a DateTime<Utc>>,
+    ) -> T::Total {
+        let row = row.to_string();
+        T::total(
+            cols.into_iter()
+                .filter_map(|c| self.content.get(&(row.clone(), *c))),
+            self.period,
+        )
+    }
+
+    fn col_total(&self, col: &DateTime<Utc>) -> T::Total {
+        T::total(
+            self.content
+                .iter()
+                .filter(|((_, c), _)| c == col)
+                .map(|(_, v)| v),
+            self.period,
+        )
+    }
+
+    fn total<'a>(&self, cols: impl IntoIterator<Item = &'a DateTime<Utc>>) -> T::Total {
+        let cols: HashSet<&DateTime<Utc>> = cols.into_iter().collect();
+        T::total(
+            self.content
+                .iter()
+                .filter(|((_, c), _)| cols.contains(c))
+                .map(|(_, v)| v),
+            self.period,
+        )
+    }
+
+    fn period(&self) -> Period {
+        self.period
+    }
 }
 
 impl<T> MyTable<T> {
@@ -82,16 +280,211 @@ impl<T> MyTable<T> {
         self.content.insert((row, col), item)
     }
 
+    /// Remove a row and its values
+    pub(super) fn remove_row(&mut self, row: &str) {
+        self.row_headers.remove(row);
+        self.content.retain(|(r, _), _| r != row);
+    }
+
+    /// Set the period of the columns, which are the first days of their period
+    pub(super) fn set_period(&mut self, period: Period) {
+        self.period = period;
+    }
+
     fn get_mut(&mut self, row: String, col: DateTime<Utc>) -> Option<&mut T> {
         self.content.get_mut(&(row, col))
     }
+
+    /// Order in which the rows are iterated, the rows listed are only used by the
+    /// custom order. The order is computed once, from the current rows.
+    pub fn set_row_order(&mut self, order: RowOrder, rows: &[String])
+    where
+        T: Cell,
+    {
+        self.row_order = order;
+        self.custom_order = rows.to_vec();
+        self.ranks = match order {
+            RowOrder::Alphabetical => HashMap::new(),
+            RowOrder::Total => {
+                let totals: HashMap<&String, T::Total> = self
+                    .row_headers
+                    .iter()
+                    .map(|r| (r, self.row_total(r, &self.col_headers)))
+                    .collect();
+                let mut rows: Vec<&String> = self.row_headers.iter().collect();
+                rows.sort_by(|a, b| totals[b].partial_cmp(&totals[a]).unwrap_or(Ordering::Equal));
+                rows.into_iter()
+                    .enumerate()
+                    .map(|(i, r)| (r.clone(), i))
+                    .collect()
+            }
+            RowOrder::Custom => self
+                .row_headers
+                .iter()
+                .map(|r| (r.clone(), self.custom_rank(r)))
+                .collect(),
+        };
+    }
+
+    /// Position of the row, or of its project, in the custom order, the rows not
+    /// listed coming last
+    fn custom_rank(&self, row: &str) -> usize {
+        let project = split___(row).0;
+        self.custom_order
+            .iter()
+            .position(|r| r == row)
+            .or_else(|| self.custom_order.iter().position(|r| *r == project))
+            .unwrap_or(self.custom_order.len())
+    }
 }
 
-pub trait Tabler<'a> {
-    type Table: Table<Item<'a>: Display>
+impl<T: Cell> MyTable<T> {
+    /// Merge the tables of several users, prefixing the rows with the name of the user.
+    /// Rows of an unnamed user are kept as is. The custom order matches the rows
+    /// without their user, the other orders are computed on the merged rows.
+    pub fn merge_users<'a>(
+        tables: impl IntoIterator<Item = (&'a String, &'a MyTable<T>)>,
+    ) -> MyTable<T>
     where
-        Self: 'a;
-    fn process(entries: Vec<Entry>) -> Self::Table;
+        T: 'a,
+    {
+        let mut merged = MyTable::default();
+        let mut ranks = HashMap::new();
+        for (user, table) in tables {
+            merged.period = table.period;
+            merged.row_order = table.row_order;
+            merged.custom_order = table.custom_order.clone();
+            for ((row, col), v) in table.content.iter() {
+                let merged_row = match user.is_empty() {
+                    true => row.clone(),
+                    false => format!("{user}: {row}"),
+                };
+                ranks.insert(merged_row.clone(), table.custom_rank(row));
+                merged.insert(merged_row, *col, v.clone());
+            }
+        }
+        match merged.row_order {
+            RowOrder::Custom => merged.ranks = ranks,
+            order => merged.set_row_order(order, &[]),
+        }
+        merged
+    }
+}
+
+/// Tabler computing the table
+#[derive(ValueEnum, Serialize, Deserialize, Debug, Default, PartialEq, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum TablerKind {
+    /// Percents of each day spent on the rows
+    #[default]
+    Proportional,
+    /// Hours spent on the rows each day
+    Hours,
+    /// Percents of each day, smoothed so that months match the targets
+    Target,
+}
+
+/// Method giving the percents lost when rounding down the shares of a day, so that
+/// the day sums to 100
+#[derive(ValueEnum, Serialize, Deserialize, Debug, Default, PartialEq, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum Rounding {
+    /// To the rows with the largest remainders, ties broken by name
+    #[default]
+    LargestRemainder,
+    /// To random rows, the legacy method
+    Random,
+}
+
+pub struct TablerParam {
+    timezone: Tz,
+    rounding: Rounding,
+    granularity: u8,
+    period: Period,
+    /// Percents of the months targeted for projects or tasks
+    targets: Vec<(String, f64)>,
+    /// Duration of a full day, none when every day is full
+    capacity: Option<TimeDelta>,
+    /// Row receiving the capacity left on partial days
+    unassigned: Option<String>,
+}
+
+fn parse_target(target: &str) -> Result<(String, f64), InvalidTarget> {
+    let error = || InvalidTarget {
+        target: target.to_string(),
+    };
+    let (row, percent) = split_eq(target).map_err(|_| error())?;
+    let percent: f64 = percent.trim().parse().map_err(|_| error())?;
+    if !(0.0..=100.0).contains(&percent) {
+        return Err(error());
+    }
+    Ok((row.trim().to_string(), percent))
+}
+
+impl TablerParam {
+    pub fn build(args: &Args) -> Result<TablerParam, Box<dyn Error>> {
+        if args.granularity == 0 || 100 % args.granularity != 0 {
+            return Err(InvalidGranularity(args.granularity).into());
+        }
+
+        let capacity = match args.capacity {
+            Some(hours) if !(hours > 0.0 && hours <= 24.0) => {
+                return Err(InvalidCapacity(hours).into())
+            }
+            Some(hours) => Some(TimeDelta::seconds((hours * 3600.0).round() as i64)),
+            None => None,
+        };
+        if capacity.is_some() {
+            if args.period != Period::Day {
+                let period = format!(
+                    "--period {}",
+                    args.period.to_possible_value().unwrap().get_name()
+                );
+                return Err(CapacityConflict(period).into());
+            }
+            if args.tabler == TablerKind::Target {
+                return Err(CapacityConflict(String::from("--tabler target")).into());
+            }
+        }
+
+        let mut targets = Vec::new();
+        if let Some(path) = &args.target_file {
+            for line in read_to_string(path)?.lines() {
+                let line = line.trim();
+                if !line.is_empty() && !line.starts_with('#') {
+                    targets.push(parse_target(line)?);
+                }
+            }
+        }
+        for t in &args.target {
+            targets.push(parse_target(t)?);
+        }
+
+        Ok(TablerParam {
+            timezone: args.timezone()?,
+            rounding: args.rounding,
+            granularity: args.granularity,
+            period: args.period,
+            targets,
+            capacity,
+            unassigned: args.unassigned.clone(),
+        })
+    }
+}
+
+pub trait Tabler {
+    /// Type of the cells of the table, displayed by the exporters
+    type Cell: Cell;
+    fn process(entries: Vec<Entry>, param: &TablerParam) -> MyTable<Self::Cell>;
+
+    /// Report on the computed table displayed to the user, none by default
+    fn report(
+        _entries: &[Entry],
+        _table: &MyTable<Self::Cell>,
+        _param: &TablerParam,
+    ) -> Option<String> {
+        None
+    }
 }
 
 #[cfg(test)]
@@ -118,6 +511,203 @@ mod tests {
         assert_eq!(2, v);
     }
 
+    #[test]
+    fn merge_users() {
+        let now = Utc::now();
+        let mut alice: MyTable<u8> = MyTable::default();
+        alice.insert("Project1".to_string(), now, 60);
+        alice.insert("Project2".to_string(), now, 40);
+        let mut unnamed: MyTable<u8> = MyTable::default();
+        unnamed.insert("Project1".to_string(), now, 100);
+
+        let users = [(String::from("Alice"), alice), (String::new(), unnamed)];
+        let t = MyTable::merge_users(users.iter().map(|(u, t)| (u, t)));
+        assert_eq!(t.row_headers().count(), 3);
+        assert_eq!(t.get("Alice: Project1".to_string(), now), 60);
+        assert_eq!(t.get("Alice: Project2".to_string(), now), 40);
+        assert_eq!(t.get("Project1".to_string(), now), 100);
+    }
+
+    #[test]
+    fn granularity() {
+        let args = |granularity| Args {
+            granularity,
+            timezone: Some(String::from("UTC")),
+            ..Default::default()
+        };
+        assert!(TablerParam::build(&args(25)).is_ok());
+        for g in [0, 30] {
+            let error = TablerParam::build(&args(g)).err().unwrap();
+            assert_eq!(error.downcast_ref(), Some(&InvalidGranularity(g)));
+        }
+    }
+
+    #[test]
+    fn capacity() {
+        let args = |capacity| Args {
+            capacity,
+            timezone: Some(String::from("UTC")),
+            ..Default::default()
+        };
+        let param = TablerParam::build(&args(Some(7.5))).unwrap();
+        assert_eq!(param.capacity, Some(TimeDelta::minutes(450)));
+        assert_eq!(TablerParam::build(&args(None)).unwrap().capacity, None);
+        for c in [0.0, -1.0, 25.0, f64::NAN] {
+            let error = TablerParam::build(&args(Some(c))).err().unwrap();
+            assert!(error.downcast_ref::<InvalidCapacity>().is_some());
+        }
+
+        let error = TablerParam::build(&Args {
+            period: Period::Week,
+            ..args(Some(8.0))
+        })
+        .err()
+        .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "--capacity is daily and cannot be used with --period week"
+        );
+        let error = TablerParam::build(&Args {
+            tabler: TablerKind::Target,
+            ..args(Some(8.0))
+        })
+        .err()
+        .unwrap();
+        assert!(error.downcast_ref::<CapacityConflict>().is_some());
+    }
+
+    #[test]
+    fn targets() {
+        let path = std::env::temp_dir().join("ttm_targets.txt");
+        std::fs::write(&path, "# Contract\nProject1=60\n\nProject2___Task = 30\n").unwrap();
+        let param = TablerParam::build(&Args {
+            target: vec![String::from("Project3=10")],
+            target_file: Some(path.to_string_lossy().to_string()),
+            timezone: Some(String::from("UTC")),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(
+            param.targets,
+            vec![
+                (String::from("Project1"), 60.0),
+                (String::from("Project2___Task"), 30.0),
+                (String::from("Project3"), 10.0),
+            ]
+        );
+
+        for target in ["Project1", "Project1=many", "Project1=120"] {
+            let error = TablerParam::build(&Args {
+                target: vec![target.to_string()],
+                ..Default::default()
+            })
+            .err()
+            .unwrap();
+            assert_eq!(
+                error.downcast_ref(),
+                Some(&InvalidTarget {
+                    target: target.to_string()
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn totals() {
+        let mut t: MyTable<u8> = MyTable::default();
+        let day = Utc.with_ymd_and_hms(2024, 10, 14, 0, 0, 0).unwrap();
+        let next = Utc.with_ymd_and_hms(2024, 10, 15, 0, 0, 0).unwrap();
+        t.insert(String::from("Project1"), day, 75);
+        t.insert(String::from("Project2"), day, 25);
+        t.insert(String::from("Project1"), next, 100);
+
+        assert_eq!(
+            t.row_total("Project1", [&day, &next]),
+            Periods(1.75, Period::Day)
+        );
+        assert_eq!(t.row_total("Project1", [&day]).to_string(), "0.75 days");
+        assert_eq!(t.row_total("Project3", [&day]), Periods(0.0, Period::Day));
+        assert_eq!(t.col_total(&day), Periods(1.0, Period::Day));
+        assert_eq!(t.total([&day, &next]).to_string(), "2.00 days");
+
+        t.set_period(Period::Week);
+        assert_eq!(t.total([&day, &next]).to_string(), "2.00 weeks");
+        t.set_period(Period::Month);
+        assert_eq!(t.col_total(&day).to_string(), "1.00 months");
+    }
+
+    #[test]
+    fn row_order() {
+        let day = Utc.with_ymd_and_hms(2024, 10, 14, 0, 0, 0).unwrap();
+        let next = Utc.with_ymd_and_hms(2024, 10, 13, 0, 0, 0).unwrap();
+        let table = || {
+            let mut t: MyTable<u8> = MyTable::default();
+            t.insert(String::from("B"), day, 20);
+            t.insert(String::from("C___Task"), day, 50);
+            t.insert(String::from("A"), day, 30);
+            t.insert(String::from("B"), next, 40);
+            t.insert(String::from("C"), next, 60);
+            t
+        };
+        let mut t = table();
+        let rows = |t: &MyTable<u8>| t.row_headers().cloned().collect::<Vec<_>>();
+
+        assert_eq!(rows(&t), vec!["A", "B", "C", "C___Task"]);
+        assert_eq!(t.col_headers().collect::<Vec<_>>(), vec![&next, &day]);
+
+        t.set_row_order(RowOrder::Total, &[]);
+        assert_eq!(rows(&t), vec!["B", "C", "C___Task", "A"]);
+
+        t.set_row_order(RowOrder::Custom, &[String::from("C"), String::from("A")]);
+        assert_eq!(rows(&t), vec!["C", "C___Task", "A", "B"]);
+        // Rows added after the order come last
+        t.insert(String::from("0"), day, 0);
+        assert_eq!(rows(&t).last().unwrap(), "0");
+
+        let custom = [String::from("C"), String::from("A")];
+        let mut bob = table();
+        bob.set_row_order(RowOrder::Custom, &custom);
+        let users = BTreeMap::from([(String::new(), t), (String::from("bob"), bob)]);
+        let merged = MyTable::merge_users(users.iter());
+        assert_eq!(
+            rows(&merged).join(", "),
+            "C, C___Task, bob: C, bob: C___Task, A, bob: A, 0, B, bob: B"
+        );
+    }
+
+    #[test]
+    fn period_labels() {
+        let day = NaiveDate::from_ymd_opt(2024, 10, 17).unwrap();
+        assert_eq!(
+            Period::Week.start(day),
+            NaiveDate::from_ymd_opt(2024, 10, 14).unwrap()
+        );
+        assert_eq!(
+            Period::Month.start(day),
+            NaiveDate::from_ymd_opt(2024, 10, 1).unwrap()
+        );
+
+        let col = Utc.with_ymd_and_hms(2024, 10, 14, 0, 0, 0).unwrap();
+        assert_eq!(Period::Day.label(&col), "14");
+        assert_eq!(Period::Week.label(&col), "2024-W42");
+        assert_eq!(Period::Month.label(&col), "2024-10");
+
+        // The first ISO week of 2025 starts in 2024
+        let col = Utc.with_ymd_and_hms(2024, 12, 30, 0, 0, 0).unwrap();
+        assert_eq!(Period::Week.label(&col), "2025-W01");
+
+        let mut t: MyTable<u8> = MyTable::default();
+        t.set_period(Period::Week);
+        t.insert(String::from("A"), col - TimeDelta::days(7), 100);
+        t.insert(String::from("A"), col, 100);
+        let labels: Vec<String> = t
+            .groups()
+            .iter()
+            .map(|(g, cols)| format!("{} {}", Period::Week.group_label(g), cols.len()))
+            .collect();
+        assert_eq!(labels, vec!["2024 1", "2025 1"]);
+    }
+
     #[test]
     fn get_default() {
         let t: MyTable<u8> = MyTable::default();
@@ -0,0 +1,165 @@
+use std::marker::PhantomData;
+
+use crate::{entries::Entry, utils::midnight};
+
+use super::{MyTable, Period, Tabler, TablerParam};
+
+pub struct Aggregate<T: Tabler> {
+    tabler: PhantomData<T>,
+}
+
+///
+/// Compute the table of the tabler `T` with one column per period (day, ISO week or month)
+/// Entries are moved to the first day of their period, keeping their duration, so that
+/// `T` computes the whole period in a single column.
+///
+impl<T: Tabler> Tabler for Aggregate<T> {
+    type Cell = T::Cell;
+
+    fn process(entries: Vec<Entry>, param: &TablerParam) -> MyTable<T::Cell> {
+        if param.period == Period::Day {
+            return T::process(entries, param);
+        }
+
+        let mut table = T::process(move_to_period(entries, param), param);
+        table.set_period(param.period);
+        table
+    }
+
+    fn report(entries: &[Entry], table: &MyTable<T::Cell>, param: &TablerParam) -> Option<String> {
+        match param.period {
+            Period::Day => T::report(entries, table, param),
+            _ => T::report(&move_to_period(entries.to_vec(), param), table, param),
+        }
+    }
+}
+
+/// Move the entries to the first day of their period, keeping their duration
+fn move_to_period(entries: Vec<Entry>, param: &TablerParam) -> Vec<Entry> {
+    let tz = &param.timezone;
+    entries
+        .into_iter()
+        .map(|e| {
+            let day = param.period.start(e.start.with_timezone(tz).date_naive());
+            let start = midnight(tz, day);
+            Entry {
+                end: start + e.duration(),
+                start,
+                ..e
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+    use chrono_tz::Tz;
+
+    use crate::tablers::{
+        hours::{DecimalHours, Hours},
+        proportional::Proportional,
+        Table,
+    };
+
+    use super::*;
+
+    fn param(period: Period) -> TablerParam {
+        TablerParam {
+            timezone: Tz::Europe__Paris,
+            rounding: Default::default(),
+            granularity: 1,
+            period,
+            targets: Vec::new(),
+            capacity: None,
+            unassigned: None,
+        }
+    }
+
+    fn entry(project: &str, start: DateTime<Utc>, hours: i64) -> Entry {
+        Entry {
+            project: project.to_string(),
+            start,
+            end: start + TimeDelta::hours(hours),
+            ..Default::default()
+        }
+    }
+
+    fn entries() -> Vec<Entry> {
+        vec![
+            // Monday 14 and Sunday 20 of October, week 42
+            entry(
+                "project1",
+                Utc.with_ymd_and_hms(2024, 10, 14, 9, 0, 0).unwrap(),
+                3,
+            ),
+            entry(
+                "project2",
+                Utc.with_ymd_and_hms(2024, 10, 20, 9, 0, 0).unwrap(),
+                1,
+            ),
+            // Monday 21 of October at 00:30 in Paris, week 43
+            entry(
+                "project1",
+                Utc.with_ymd_and_hms(2024, 10, 20, 22, 30, 0).unwrap(),
+                4,
+            ),
+            // Friday 1 of November, week 44
+            entry(
+                "project2",
+                Utc.with_ymd_and_hms(2024, 11, 1, 9, 0, 0).unwrap(),
+                2,
+            ),
+        ]
+    }
+
+    fn day(m: u32, d: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, m, d, 0, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn weekly_hours() {
+        let table = Aggregate::<Hours>::process(entries(), &param(Period::Week));
+
+        assert_eq!(table.period(), Period::Week);
+        let mut cols: Vec<String> = table
+            .col_headers()
+            .map(|c| table.period().label(c))
+            .collect();
+        cols.sort();
+        assert_eq!(cols, vec!["2024-W42", "2024-W43", "2024-W44"]);
+        assert_eq!(
+            table.get(String::from("project1"), day(10, 14)),
+            DecimalHours(TimeDelta::hours(3))
+        );
+        assert_eq!(
+            table.get(String::from("project2"), day(10, 14)),
+            DecimalHours(TimeDelta::hours(1))
+        );
+        assert_eq!(
+            table.get(String::from("project1"), day(10, 21)),
+            DecimalHours(TimeDelta::hours(4))
+        );
+        assert_eq!(
+            table.get(String::from("project2"), day(10, 28)),
+            DecimalHours(TimeDelta::hours(2))
+        );
+    }
+
+    #[test]
+    fn monthly_proportional() {
+        let table = Aggregate::<Proportional>::process(entries(), &param(Period::Month));
+
+        assert_eq!(table.col_headers().count(), 2);
+        assert_eq!(table.get(String::from("project1"), day(10, 1)), 88);
+        assert_eq!(table.get(String::from("project2"), day(10, 1)), 12);
+        assert_eq!(table.get(String::from("project2"), day(11, 1)), 100);
+    }
+
+    #[test]
+    fn daily() {
+        let table = Aggregate::<Hours>::process(entries(), &param(Period::Day));
+        assert_eq!(table.period(), Period::Day);
+        assert_eq!(table.col_headers().count(), 4);
+    }
+}
@@ -0,0 +1,112 @@
+use std::fmt::{Display, Formatter};
+
+use chrono::TimeDelta;
+
+use super::{Cell, MyTable, Period, Tabler, TablerParam};
+
+/// Duration displayed in decimal hours, 7.50 for 7h30
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DecimalHours(pub TimeDelta);
+
+impl Display for DecimalHours {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.2}", self.0.num_seconds() as f64 / 3600.0)
+    }
+}
+
+impl Cell for DecimalHours {
+    type Total = DecimalHours;
+
+    fn total<'a>(cells: impl Iterator<Item = &'a DecimalHours>, _: Period) -> DecimalHours {
+        DecimalHours(cells.map(|c| c.0).sum())
+    }
+}
+
+pub struct Hours {}
+
+///
+/// Compute table containing the daily sum of the durations of the entries related to same project and task
+///
+impl Tabler for Hours {
+    type Cell = DecimalHours;
+
+    fn process(entries: Vec<crate::entries::Entry>, param: &TablerParam) -> MyTable<DecimalHours> {
+        let mut table = MyTable::default();
+
+        for e in entries {
+            let d = e.get_start_day(&param.timezone);
+
+            match table.get_mut(e.to_project___task(), d) {
+                Some(DecimalHours(v)) => *v += e.duration(),
+                None => {
+                    table.insert(e.to_project___task(), d, DecimalHours(e.duration()));
+                }
+            }
+        }
+
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use chrono_tz::Tz;
+
+    use crate::{entries::Entry, tablers::Table};
+
+    use super::*;
+
+    #[test]
+    fn decimal_hours() {
+        let hours = DecimalHours(TimeDelta::minutes(450));
+        assert_eq!(hours.to_string(), "7.50");
+        assert_eq!(DecimalHours(TimeDelta::minutes(20)).to_string(), "0.33");
+        assert_eq!(DecimalHours::default().to_string(), "0.00");
+    }
+
+    #[test]
+    fn daily_sum() {
+        let day = Utc.with_ymd_and_hms(2024, 10, 12, 0, 0, 0).unwrap();
+        let entry = |project: &str, hour: i64, minutes: i64| Entry {
+            project: project.to_string(),
+            start: day + TimeDelta::hours(hour),
+            end: day + TimeDelta::hours(hour) + TimeDelta::minutes(minutes),
+            ..Default::default()
+        };
+        let entries = vec![
+            entry("project1", 9, 90),
+            entry("project2", 11, 60),
+            entry("project1", 14, 45),
+            entry("project1", 33, 60),
+        ];
+        let param = TablerParam {
+            timezone: Tz::UTC,
+            rounding: Default::default(),
+            granularity: 1,
+            period: Default::default(),
+            targets: Vec::new(),
+            capacity: None,
+            unassigned: None,
+        };
+        let table = Hours::process(entries, &param);
+
+        assert_eq!(table.col_headers().len(), 2);
+        assert_eq!(
+            table.get(String::from("project1"), day),
+            DecimalHours(TimeDelta::minutes(135))
+        );
+        assert_eq!(
+            table.get(String::from("project2"), day),
+            DecimalHours(TimeDelta::hours(1))
+        );
+        assert_eq!(
+            table.get(String::from("project1"), day + TimeDelta::days(1)),
+            DecimalHours(TimeDelta::hours(1))
+        );
+        let days = [day, day + TimeDelta::days(1)];
+        assert_eq!(table.row_total("project1", &days).to_string(), "3.25");
+        assert_eq!(table.col_total(&day).to_string(), "3.25");
+        assert_eq!(table.total(&days).to_string(), "4.25");
+    }
+}
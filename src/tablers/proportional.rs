@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use chrono::{DateTime, TimeDelta, Utc};
 
@@ -6,24 +6,29 @@ use rand::prelude::Distribution;
 use rand::rngs::StdRng;
 use rand::{distributions::Uniform, SeedableRng};
 
-use super::{MyTable, Table, Tabler};
+use super::{MyTable, Rounding, Table, Tabler, TablerParam};
+
+/// Row tracking the capacity left on partial days when there is no unassigned row
+const LEFTOVER: &str = "\u{0}leftover";
 
 pub struct Proportional {}
 
 ///
 /// Compute table containing the daily sum for each entry related to same project and task divided by the sum of entries
+/// Percents are rounded down, then the percents missing for a day to sum to 100 are
+/// given according to the rounding method.
+/// With a capacity, days with less tracked time only sum to their share of the capacity,
+/// the rest going to the unassigned row if any.
 ///
-impl<'a> Tabler<'a> for Proportional {
-    type Table = MyTable<u8>
-    where
-        Self: 'a;
+impl Tabler for Proportional {
+    type Cell = u8;
 
-    fn process(entries: Vec<crate::entries::Entry>) -> Self::Table {
+    fn process(entries: Vec<crate::entries::Entry>, param: &TablerParam) -> MyTable<u8> {
         let mut delta: MyTable<TimeDelta> = MyTable::default();
         let mut days: HashMap<DateTime<Utc>, TimeDelta> = HashMap::new();
 
         for e in entries {
-            let d = e.get_start_day();
+            let d = e.get_start_day(&param.timezone);
 
             match delta.insert(e.to_project___task(), d, e.duration()) {
                 Some(old) => {
@@ -42,74 +47,219 @@ impl<'a> Tabler<'a> for Proportional {
             }
         }
 
-        let mut table = Self::Table::default();
-
-        for s in delta.row_headers() {
-            for d in delta.col_headers() {
-                let x = delta.get(s.to_string(), *d);
-                if x.is_zero() {
-                    continue;
+        // The capacity left on partial days is tracked on a row, removed from the table
+        // when there is no unassigned row so that the day is partial
+        let leftover = param
+            .unassigned
+            .clone()
+            .unwrap_or_else(|| String::from(LEFTOVER));
+        if let Some(capacity) = param.capacity {
+            for (d, total) in days.iter_mut().filter(|(_, t)| **t < capacity) {
+                let left = capacity - *total;
+                match delta.get_mut(leftover.clone(), *d) {
+                    Some(v) => *v += left,
+                    None => {
+                        delta.insert(leftover.clone(), *d, left);
+                    }
                 }
-                let v = 100 * x.num_seconds() / days.get(d).unwrap().num_seconds();
-                let v = u8::try_from(v).unwrap();
-                table.insert(s.to_string(), *d, v);
+                *total = capacity;
             }
         }
 
-        // Compute sum per day
-        let mut days: HashMap<DateTime<Utc>, u8> = HashMap::new();
+        let mut table = match param.granularity > 1 {
+            true => quantize(&delta, &days, param.granularity),
+            false => round(&delta, &days, param.rounding),
+        };
+        if param.unassigned.is_none() {
+            table.remove_row(LEFTOVER);
+        }
 
-        for s in table.row_headers() {
-            for d in table.col_headers() {
-                let v = table.get(s.to_string(), *d);
+        table
+    }
+}
 
-                match days.insert(*d, v) {
-                    Some(old) => {
-                        let new = days.get_mut(&d).unwrap();
-                        *new += old;
-                    }
-                    None => {}
-                }
+/// Round down the percents of each row, then give the percents missing for each day to
+/// sum to 100 according to the rounding method
+fn round(
+    delta: &MyTable<TimeDelta>,
+    days: &HashMap<DateTime<Utc>, TimeDelta>,
+    rounding: Rounding,
+) -> MyTable<u8> {
+    let mut table = MyTable::default();
+
+    for s in delta.row_headers() {
+        for d in delta.col_headers() {
+            let x = delta.get(s.to_string(), *d);
+            if x.is_zero() {
+                continue;
             }
+            let v = 100 * x.num_seconds() / days.get(d).unwrap().num_seconds();
+            let v = u8::try_from(v).unwrap();
+            table.insert(s.to_string(), *d, v);
         }
+    }
 
-        let mut rng = StdRng::seed_from_u64(1);
+    match rounding {
+        Rounding::LargestRemainder => largest_remainder(&mut table, delta, days),
+        Rounding::Random => random(&mut table),
+    }
 
-        // Randomly adjust values so that total per day is 100
-        for d in days.clone().keys().into_iter() {
-            let n = *days.entry(*d).or_insert(100);
-            if n == 100 {
-                continue;
-            }
+    table
+}
+
+/// Add the percents missing from each day to rows picked at random, from a fixed seed
+fn random(table: &mut MyTable<u8>) {
+    // Compute sum per day
+    let mut days: BTreeMap<DateTime<Utc>, u8> = BTreeMap::new();
 
-            let mut rows = Vec::new();
-            for row in table.row_headers.clone().into_iter() {
-                if table.get(row.to_string(), *d) != 0 {
-                    rows.push(row.clone());
+    for s in table.row_headers() {
+        for d in table.col_headers() {
+            let v = table.get(s.to_string(), *d);
+
+            match days.insert(*d, v) {
+                Some(old) => {
+                    let new = days.get_mut(&d).unwrap();
+                    *new += old;
                 }
+                None => {}
             }
+        }
+    }
+
+    let mut rng = StdRng::seed_from_u64(1);
 
-            let dis = Uniform::new(0, rows.len());
+    // Randomly adjust values so that total per day is 100
+    for d in days.clone().keys().into_iter() {
+        let n = *days.entry(*d).or_insert(100);
+        if n == 100 {
+            continue;
+        }
 
-            for _ in 0..100 - n {
-                *table
-                    .get_mut(rows[dis.sample(&mut rng)].to_string(), *d)
-                    .unwrap() += 1;
+        let mut rows = Vec::new();
+        for row in table.row_headers.clone().into_iter() {
+            if table.get(row.to_string(), *d) != 0 {
+                rows.push(row.clone());
             }
         }
 
-        table
+        let dis = Uniform::new(0, rows.len());
+
+        for _ in 0..100 - n {
+            *table
+                .get_mut(rows[dis.sample(&mut rng)].to_string(), *d)
+                .unwrap() += 1;
+        }
+    }
+}
+
+/// Add the percents missing from each day to the rows having the largest remainders
+/// of their exact share (largest remainder or Hamilton method), ties being broken by
+/// row name
+fn largest_remainder(
+    table: &mut MyTable<u8>,
+    delta: &MyTable<TimeDelta>,
+    days: &HashMap<DateTime<Utc>, TimeDelta>,
+) {
+    for (d, total) in days {
+        let total = total.num_seconds();
+        let mut rows: Vec<(i64, &String)> = delta
+            .row_headers()
+            .filter(|r| !delta.get(r.to_string(), *d).is_zero())
+            .map(|r| (100 * delta.get(r.to_string(), *d).num_seconds() % total, r))
+            .collect();
+        let sum: u8 = rows.iter().map(|(_, r)| table.get(r.to_string(), *d)).sum();
+
+        rows.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+        for (_, r) in rows.into_iter().take(usize::from(100 - sum)) {
+            *table.get_mut(r.to_string(), *d).unwrap() += 1;
+        }
+    }
+}
+
+/// Millionths of a slot, to apportion slots with integers
+const MICRO: i64 = 1_000_000;
+
+/// Apportion each day in slots of `granularity` percents, days being processed in
+/// chronological order.
+/// The difference between the exact share of a row and its slots is carried to the
+/// next days, so that the cumulative error of each row stays small over the month.
+/// Slots left by rounding down go to the largest remainders, then to the largest
+/// shares so that small rows roll into larger ones, ties being broken by row name.
+fn quantize(
+    delta: &MyTable<TimeDelta>,
+    days: &HashMap<DateTime<Utc>, TimeDelta>,
+    granularity: u8,
+) -> MyTable<u8> {
+    let slots = i64::from(100 / granularity);
+    let mut carry: HashMap<&String, i64> = HashMap::new();
+    let mut table = MyTable::default();
+
+    let mut rows: Vec<&String> = delta.row_headers().collect();
+    rows.sort();
+    let mut days: Vec<(&DateTime<Utc>, &TimeDelta)> = days.iter().collect();
+    days.sort();
+
+    for (d, total) in days {
+        // Exact share of each row and its target including the carried error
+        let shares: Vec<(&String, i64, i64)> = rows
+            .iter()
+            .filter(|r| !delta.get(r.to_string(), *d).is_zero())
+            .map(|r| {
+                let x = delta.get(r.to_string(), *d).num_seconds();
+                let exact = slots * MICRO * x / total.num_seconds();
+                let target = (exact + carry.get(r).copied().unwrap_or_default()).max(0);
+                (*r, exact, target)
+            })
+            .collect();
+        let sum = shares.iter().map(|s| s.2).sum::<i64>().max(1);
+
+        // Targets are scaled so that the day has exactly its slots
+        let mut allocations: Vec<(&String, i64, i64, i64)> = shares
+            .into_iter()
+            .map(|(r, exact, target)| {
+                let t = target * slots * MICRO / sum;
+                (r, exact, t / MICRO, t % MICRO)
+            })
+            .collect();
+        let left = slots - allocations.iter().map(|a| a.2).sum::<i64>();
+        let left = usize::try_from(left).unwrap_or_default();
+        allocations.sort_by(|a, b| b.3.cmp(&a.3).then(b.1.cmp(&a.1)).then(a.0.cmp(b.0)));
+        for a in allocations.iter_mut().take(left) {
+            a.2 += 1;
+        }
+
+        for (r, exact, n, _) in allocations {
+            *carry.entry(r).or_default() += exact - n * MICRO;
+            let v = u8::try_from(n * i64::from(granularity)).unwrap();
+            table.insert(r.to_string(), *d, v);
+        }
     }
+
+    table
 }
 
 #[cfg(test)]
 mod tests {
     use chrono::TimeZone;
+    use chrono_tz::Tz;
+    use proptest::prelude::*;
 
-    use crate::entries::Entry;
+    use crate::{entries::Entry, tablers::Period};
 
     use super::*;
 
+    fn utc() -> TablerParam {
+        TablerParam {
+            timezone: Tz::UTC,
+            rounding: Rounding::LargestRemainder,
+            granularity: 1,
+            period: Period::Day,
+            targets: Vec::new(),
+            capacity: None,
+            unassigned: None,
+        }
+    }
+
     #[test]
     fn entry_default() {
         let day = Utc.with_ymd_and_hms(2024, 10, 12, 0, 0, 0).unwrap();
@@ -118,7 +268,7 @@ mod tests {
             end: day.checked_add_signed(TimeDelta::hours(14)).unwrap(),
             ..Default::default()
         }];
-        let table = Proportional::process(entries);
+        let table = Proportional::process(entries, &utc());
         assert_eq!(table.col_headers().len(), 1);
         assert_eq!(table.row_headers().len(), 1);
     }
@@ -154,7 +304,7 @@ mod tests {
             },
             e2.clone(),
         ];
-        let table = Proportional::process(entries);
+        let table = Proportional::process(entries, &utc());
 
         assert_eq!(table.col_headers().len(), 1);
         assert_eq!(table.row_headers().len(), 2);
@@ -189,7 +339,7 @@ mod tests {
                 ..Default::default()
             },
         ];
-        let table = Proportional::process(entries);
+        let table = Proportional::process(entries, &utc());
 
         let sum: u8 = table.row_headers().map(|r| table.get(r.clone(), day)).sum();
 
@@ -197,4 +347,196 @@ mod tests {
         assert_eq!(table.row_headers().len(), 3);
         assert_eq!(sum, 100);
     }
+
+    #[test]
+    fn largest_remainder_ties_by_name() {
+        let day = Utc.with_ymd_and_hms(2024, 10, 12, 0, 0, 0).unwrap();
+        let entry = |project: &str, hour: i64, minutes: i64| Entry {
+            project: project.to_string(),
+            start: day + TimeDelta::hours(hour),
+            end: day + TimeDelta::hours(hour) + TimeDelta::minutes(minutes),
+            ..Default::default()
+        };
+
+        // Equal thirds: the missing percent goes to the first project by name
+        let entries = vec![
+            entry("project3", 9, 60),
+            entry("project1", 10, 60),
+            entry("project2", 11, 60),
+        ];
+        let table = Proportional::process(entries, &utc());
+        assert_eq!(table.get(String::from("project1"), day), 34);
+        assert_eq!(table.get(String::from("project2"), day), 33);
+        assert_eq!(table.get(String::from("project3"), day), 33);
+
+        // 66.3, 32.6 and 1.1: the largest remainder wins over the smallest project
+        let entries = vec![
+            entry("big", 9, 597),
+            entry("medium", 10, 293),
+            entry("tiny", 11, 10),
+        ];
+        let table = Proportional::process(entries, &utc());
+        assert_eq!(table.get(String::from("big"), day), 66);
+        assert_eq!(table.get(String::from("medium"), day), 33);
+        assert_eq!(table.get(String::from("tiny"), day), 1);
+    }
+
+    fn entries() -> impl Strategy<Value = Vec<Entry>> {
+        let day = Utc.with_ymd_and_hms(2024, 10, 12, 0, 0, 0).unwrap();
+        prop::collection::vec((0..12usize, 0..3i64, 0..1440i64, 1..600i64), 1..40).prop_map(
+            move |entries| {
+                entries
+                    .into_iter()
+                    .map(|(project, d, minute, minutes)| {
+                        let start = day + TimeDelta::days(d) + TimeDelta::minutes(minute);
+                        Entry {
+                            project: format!("project{project}"),
+                            start,
+                            end: start + TimeDelta::minutes(minutes),
+                            ..Default::default()
+                        }
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn days_sum_to_100(entries in entries(), random in any::<bool>()) {
+            let param = TablerParam {
+                timezone: Tz::UTC,
+                rounding: match random {
+                    true => Rounding::Random,
+                    false => Rounding::LargestRemainder,
+                },
+                ..utc()
+            };
+            let table = Proportional::process(entries, &param);
+            for d in table.col_headers() {
+                let sum: u32 = table
+                    .row_headers()
+                    .map(|r| u32::from(table.get(r.clone(), *d)))
+                    .sum();
+                prop_assert_eq!(sum, 100);
+            }
+        }
+
+        #[test]
+        fn quantized_days_sum_to_100(
+            entries in entries(),
+            granularity in prop::sample::select(vec![5u8, 10, 20, 25, 50]),
+        ) {
+            let param = TablerParam {
+                granularity,
+                ..utc()
+            };
+            let table = Proportional::process(entries, &param);
+            for d in table.col_headers() {
+                let mut sum = 0;
+                for r in table.row_headers() {
+                    let v = table.get(r.clone(), *d);
+                    prop_assert_eq!(v % granularity, 0);
+                    sum += u32::from(v);
+                }
+                prop_assert_eq!(sum, 100);
+            }
+        }
+
+        #[test]
+        fn largest_remainder_is_deterministic(entries in entries()) {
+            let mut reversed = entries.clone();
+            reversed.reverse();
+            let table = Proportional::process(entries, &utc());
+            let other = Proportional::process(reversed, &utc());
+            for r in table.row_headers() {
+                for d in table.col_headers() {
+                    prop_assert_eq!(table.get(r.clone(), *d), other.get(r.clone(), *d));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn partial_days() {
+        let day = Utc.with_ymd_and_hms(2024, 10, 14, 0, 0, 0).unwrap();
+        let next = day + TimeDelta::days(1);
+        let entry = |project: &str, start: DateTime<Utc>, minutes: i64| Entry {
+            project: project.to_string(),
+            start: start + TimeDelta::hours(9),
+            end: start + TimeDelta::hours(9) + TimeDelta::minutes(minutes),
+            ..Default::default()
+        };
+        // 2 hours before leave, then a long day
+        let entries = vec![
+            entry("project1", day, 120),
+            entry("project1", next, 300),
+            entry("project2", next, 240),
+        ];
+        let param = TablerParam {
+            capacity: Some(TimeDelta::minutes(450)),
+            ..utc()
+        };
+
+        let table = Proportional::process(entries.clone(), &param);
+        assert_eq!(table.get(String::from("project1"), day), 27);
+        assert_eq!(table.get(String::from("project1"), next), 56);
+        assert_eq!(table.get(String::from("project2"), next), 44);
+        assert_eq!(table.row_headers().len(), 2);
+
+        let table = Proportional::process(
+            entries.clone(),
+            &TablerParam {
+                unassigned: Some(String::from("Unassigned")),
+                ..param
+            },
+        );
+        assert_eq!(table.get(String::from("project1"), day), 27);
+        assert_eq!(table.get(String::from("Unassigned"), day), 73);
+        assert_eq!(table.get(String::from("Unassigned"), next), 0);
+
+        let param = TablerParam {
+            capacity: Some(TimeDelta::minutes(450)),
+            granularity: 25,
+            ..utc()
+        };
+        let table = Proportional::process(entries, &param);
+        assert_eq!(table.get(String::from("project1"), day), 25);
+        assert_eq!(table.get(String::from("project1"), next), 50);
+        assert_eq!(table.get(String::from("project2"), next), 50);
+    }
+
+    #[test]
+    fn quarter_days() {
+        let first = Utc.with_ymd_and_hms(2024, 10, 14, 0, 0, 0).unwrap();
+        let param = TablerParam {
+            granularity: 25,
+            ..utc()
+        };
+
+        // 60%, 30% and 10% during four days
+        let mut entries = Vec::new();
+        for d in 0..4 {
+            let day = first + TimeDelta::days(d);
+            let projects = [("big", 9, 360), ("medium", 15, 180), ("tiny", 18, 60)];
+            for (project, hour, minutes) in projects {
+                entries.push(Entry {
+                    project: project.to_string(),
+                    start: day + TimeDelta::hours(hour),
+                    end: day + TimeDelta::hours(hour) + TimeDelta::minutes(minutes),
+                    ..Default::default()
+                });
+            }
+        }
+        let table = Proportional::process(entries, &param);
+
+        let values = |project: &str| -> Vec<u8> {
+            (0..4)
+                .map(|d| table.get(project.to_string(), first + TimeDelta::days(d)))
+                .collect()
+        };
+        assert_eq!(values("big"), vec![75, 50, 50, 75]);
+        assert_eq!(values("medium"), vec![25, 25, 50, 25]);
+        assert_eq!(values("tiny"), vec![0, 25, 0, 0]);
+    }
 }
@@ -0,0 +1,325 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Datelike, NaiveDate, TimeDelta, Utc};
+
+use crate::{
+    entries::Entry,
+    utils::{midnight, split___},
+};
+
+use super::{proportional::Proportional, MyTable, Table, Tabler, TablerParam};
+
+/// Maximum number of iterations of the proportional fitting
+const ITERATIONS: usize = 1000;
+
+/// Share of each row in each day: day -> row -> share of the day
+type Shares = BTreeMap<DateTime<Utc>, BTreeMap<String, f64>>;
+
+/// Shares of the tracked time of each day, grouped by month
+fn tracked(entries: &[Entry], param: &TablerParam) -> BTreeMap<NaiveDate, Shares> {
+    let mut months: BTreeMap<NaiveDate, Shares> = BTreeMap::new();
+    for e in entries {
+        let day = e.get_start_day(&param.timezone);
+        let month = day.date_naive().with_day(1).unwrap();
+        *months
+            .entry(month)
+            .or_default()
+            .entry(day)
+            .or_default()
+            .entry(e.to_project___task())
+            .or_default() += e.duration().num_milliseconds() as f64;
+    }
+
+    for shares in months.values_mut() {
+        for rows in shares.values_mut() {
+            rows.retain(|_, v| *v > 0.0);
+            let total: f64 = rows.values().sum();
+            for v in rows.values_mut() {
+                *v /= total;
+            }
+        }
+        shares.retain(|_, rows| !rows.is_empty());
+    }
+    months
+}
+
+/// Rows of a month gathered by target, the rows without target forming the last group
+struct Groups {
+    /// Group of each row
+    rows: HashMap<String, usize>,
+    /// Days tracked for each group
+    tracked: Vec<f64>,
+    /// Days each group should amount to
+    goals: Vec<f64>,
+}
+
+impl Groups {
+    /// The time not tracked on rows without target is shared between the targets
+    /// tracked during the month, in proportion to their percents
+    fn new(shares: &Shares, targets: &[(String, f64)]) -> Groups {
+        let mut rows = HashMap::new();
+        let mut tracked = vec![0.0; targets.len() + 1];
+        for (r, v) in shares.values().flat_map(|rows| rows.iter()) {
+            let project = split___(r).0;
+            let g = targets
+                .iter()
+                .position(|(t, _)| t == r)
+                .or_else(|| targets.iter().position(|(t, _)| *t == project))
+                .unwrap_or(targets.len());
+            rows.insert(r.clone(), g);
+            tracked[g] += v;
+        }
+
+        let free = tracked[targets.len()];
+        let percents: f64 = targets
+            .iter()
+            .zip(&tracked)
+            .filter(|(_, days)| **days > 0.0)
+            .map(|(t, _)| t.1)
+            .sum();
+        let goals = match percents > 0.0 {
+            true => targets
+                .iter()
+                .zip(&tracked)
+                .map(|(t, days)| match *days > 0.0 {
+                    true => (shares.len() as f64 - free) * t.1 / percents,
+                    false => 0.0,
+                })
+                .chain([free])
+                .collect(),
+            false => tracked.clone(),
+        };
+
+        Groups {
+            rows,
+            tracked,
+            goals,
+        }
+    }
+}
+
+/// Fit the shares of a month to the targets with iterative proportional fitting: the
+/// rows of each group are scaled to reach the goal of the group, then the days are
+/// scaled back to sum to 1, until both hold.
+/// Days keep the rows they were tracked on, so the result stays close to the tracked
+/// time while the months match the targets.
+fn smooth(shares: &Shares, groups: &Groups) -> Shares {
+    let mut smoothed = shares.clone();
+
+    for _ in 0..ITERATIONS {
+        let mut totals = vec![0.0; groups.goals.len()];
+        for (r, v) in smoothed.values().flat_map(|rows| rows.iter()) {
+            totals[groups.rows[r]] += v;
+        }
+        for rows in smoothed.values_mut() {
+            for (r, v) in rows.iter_mut() {
+                let g = groups.rows[r];
+                if totals[g] > 0.0 {
+                    *v *= groups.goals[g] / totals[g];
+                }
+            }
+        }
+
+        let mut error: f64 = 0.0;
+        for rows in smoothed.values_mut() {
+            let total: f64 = rows.values().sum();
+            if total <= 0.0 {
+                continue;
+            }
+            error = error.max((total - 1.0).abs());
+            for v in rows.values_mut() {
+                *v /= total;
+            }
+        }
+        if error < 1e-9 {
+            break;
+        }
+    }
+    smoothed
+}
+
+pub struct Target {}
+
+///
+/// Compute table containing the daily percents of each project and task, smoothed across the days of
+/// each month so that the month matches the targets of the parameters.
+/// Targets of a project apply to all its tasks, a task target taking precedence.
+///
+impl Tabler for Target {
+    type Cell = u8;
+
+    fn process(entries: Vec<Entry>, param: &TablerParam) -> MyTable<u8> {
+        let mut smoothed = Vec::new();
+
+        for shares in tracked(&entries, param).values() {
+            let groups = Groups::new(shares, &param.targets);
+            for (day, rows) in smooth(shares, &groups) {
+                let start = midnight(&param.timezone, day.date_naive());
+                for (row, share) in rows {
+                    let (project, task) = split___(&row);
+                    let duration = TimeDelta::milliseconds((share * 86_400_000.0).round() as i64);
+                    smoothed.push(Entry {
+                        project,
+                        task,
+                        end: start + duration,
+                        start,
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+
+        Proportional::process(smoothed, param)
+    }
+
+    /// Percents of each month tracked, targeted and in the table for each target, and
+    /// the mean daily change from the tracked percents
+    fn report(entries: &[Entry], table: &MyTable<u8>, param: &TablerParam) -> Option<String> {
+        if param.targets.is_empty() {
+            return None;
+        }
+
+        let mut lines = vec![String::from("Deviation from the tracked time")];
+        for (month, shares) in tracked(entries, param) {
+            let groups = Groups::new(&shares, &param.targets);
+            let days = shares.len() as f64;
+
+            let mut result = vec![0.0; groups.goals.len()];
+            let mut change = vec![0.0; groups.goals.len()];
+            for (day, rows) in shares.iter() {
+                for (r, tracked) in rows {
+                    let v = f64::from(table.get(r.clone(), *day)) / 100.0;
+                    result[groups.rows[r]] += v;
+                    change[groups.rows[r]] += (v - tracked).abs();
+                }
+            }
+
+            for (g, tracked) in groups.tracked.iter().enumerate() {
+                let name = match param.targets.get(g) {
+                    Some((target, _)) => target.as_str(),
+                    None if *tracked > 0.0 => "others",
+                    None => continue,
+                };
+                let month = month.format("%Y-%m");
+                if *tracked == 0.0 {
+                    lines.push(format!("{month} {name}: not tracked"));
+                    continue;
+                }
+                lines.push(format!(
+                    "{month} {name}: tracked {:.2}%, target {:.2}%, result {:.2}%, daily change {:.2}%",
+                    100.0 * tracked / days,
+                    100.0 * groups.goals[g] / days,
+                    100.0 * result[g] / days,
+                    100.0 * change[g] / days,
+                ));
+            }
+        }
+        Some(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use chrono_tz::Tz;
+
+    use crate::tablers::Rounding;
+
+    use super::*;
+
+    fn param(targets: &[(&str, f64)]) -> TablerParam {
+        TablerParam {
+            timezone: Tz::UTC,
+            rounding: Rounding::LargestRemainder,
+            granularity: 1,
+            period: Default::default(),
+            targets: targets.iter().map(|(t, p)| (t.to_string(), *p)).collect(),
+            capacity: None,
+            unassigned: None,
+        }
+    }
+
+    fn day(d: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 10, d, 0, 0, 0).unwrap()
+    }
+
+    fn entry(project: &str, task: &str, d: u32, hours: i64) -> Entry {
+        Entry {
+            project: project.to_string(),
+            task: task.to_string(),
+            start: day(d) + TimeDelta::hours(9),
+            end: day(d) + TimeDelta::hours(9 + hours),
+            ..Default::default()
+        }
+    }
+
+    /// Project A is tracked 50% of the time while its target is 60%
+    fn entries() -> Vec<Entry> {
+        vec![
+            entry("A", "", 14, 6),
+            entry("B", "", 14, 2),
+            entry("A", "", 15, 2),
+            entry("B", "", 15, 6),
+            entry("A", "", 16, 4),
+            entry("B", "", 16, 4),
+        ]
+    }
+
+    fn month_percents(table: &MyTable<u8>, row: &str) -> u32 {
+        (14..=16)
+            .map(|d| u32::from(table.get(row.to_string(), day(d))))
+            .sum()
+    }
+
+    #[test]
+    fn reach_targets() {
+        let param = param(&[("A", 60.0), ("B", 40.0)]);
+        let table = Target::process(entries(), &param);
+
+        assert_eq!(month_percents(&table, "A"), 180);
+        assert_eq!(month_percents(&table, "B"), 120);
+        for d in 14..=16 {
+            let a = table.get(String::from("A"), day(d));
+            assert_eq!(a + table.get(String::from("B"), day(d)), 100);
+        }
+        // Days keep the order of the tracked time
+        assert!(table.get(String::from("A"), day(14)) > table.get(String::from("A"), day(16)));
+        assert!(table.get(String::from("A"), day(16)) > table.get(String::from("A"), day(15)));
+
+        let report = Target::report(&entries(), &table, &param).unwrap();
+        assert!(report.contains(
+            "2024-10 A: tracked 50.00%, target 60.00%, result 60.00%, daily change 10.00%"
+        ));
+    }
+
+    #[test]
+    fn rows_without_target() {
+        let mut entries = entries();
+        entries.push(entry("C", "", 14, 8));
+        entries.push(entry("A", "Task", 15, 4));
+        let param = param(&[("A", 75.0), ("B", 25.0)]);
+        let table = Target::process(entries.clone(), &param);
+
+        // C keeps its quarter of the first day, A and B share the rest
+        assert_eq!(month_percents(&table, "C"), 50);
+        let a = month_percents(&table, "A") + month_percents(&table, "A___Task");
+        let b = month_percents(&table, "B");
+        assert!((a as i32 - 3 * b as i32).abs() <= 3, "{a} {b}");
+
+        let report = Target::report(&entries, &table, &param).unwrap();
+        assert!(report.contains("2024-10 others: tracked 16.67%, target 16.67%"));
+    }
+
+    #[test]
+    fn without_targets() {
+        let table = Target::process(entries(), &param(&[]));
+        assert_eq!(table.get(String::from("A"), day(14)), 75);
+        assert!(Target::report(&entries(), &table, &param(&[])).is_none());
+
+        let param = param(&[("A", 60.0), ("D", 40.0)]);
+        let table = Target::process(entries(), &param);
+        assert_eq!(table.get(String::from("A"), day(14)), 75);
+        let report = Target::report(&entries(), &table, &param).unwrap();
+        assert!(report.contains("2024-10 D: not tracked"));
+    }
+}
@@ -1,4 +1,5 @@
-use chrono::{DateTime, Months, TimeDelta, Utc};
+use chrono::{DateTime, Months, NaiveDate, NaiveTime, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
 
 use crate::errors::SplitError;
 
@@ -11,13 +12,13 @@ pub fn split___(s: &str) -> (String, String) {
     }
 }
 
+/// Split key=value on the first `=`, so that the value may contain other ones
 pub fn split_eq(s: &str) -> Result<(String, String), SplitError> {
-    let split: Vec<&str> = s.split("=").collect();
-    match split.len() {
-        2 => Ok((split[0].to_string(), split[1].to_string())),
-        _ => Err(SplitError {
+    match s.split_once('=') {
+        Some((k, v)) => Ok((k.to_string(), v.to_string())),
+        None => Err(SplitError {
             field: s.to_string(),
-            reason: String::from("field should contain one and only one (key=value)"),
+            reason: String::from("field should contain a key=value"),
         }),
     }
 }
@@ -28,3 +29,23 @@ pub fn end_of_month(date: &DateTime<Utc>) -> DateTime<Utc> {
         .checked_sub_signed(TimeDelta::milliseconds(1))
         .unwrap()
 }
+
+/// Timezone of the system, UTC when it cannot be found
+pub fn local_timezone() -> Tz {
+    iana_time_zone::get_timezone()
+        .ok()
+        .and_then(|name| name.parse().ok())
+        .unwrap_or(Tz::UTC)
+}
+
+/// First instant of the day in the timezone, later than midnight when a daylight
+/// saving time transition skips it
+pub fn midnight(tz: &Tz, day: NaiveDate) -> DateTime<Utc> {
+    (0..24)
+        .find_map(|h| {
+            tz.from_local_datetime(&day.and_time(NaiveTime::MIN + TimeDelta::hours(h)))
+                .earliest()
+        })
+        .expect("a day should have an hour in every timezone")
+        .to_utc()
+}
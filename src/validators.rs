@@ -0,0 +1,342 @@
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt::{Display, Formatter},
+};
+
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+use chrono_tz::Tz;
+
+use crate::{
+    args::Args,
+    calendars::{is_weekend, Calendar, LEAVE_ROW},
+    entries::Entry,
+    tablers::hours::DecimalHours,
+};
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Check which found an issue
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Check {
+    /// Entries of a user overlapping each other
+    Overlap,
+    /// Working day without any entry
+    MissingDay,
+    /// Day with more hours than the maximum
+    LongDay,
+    NoProject,
+    /// Entry whose timer was still running when downloaded
+    RunningTimer,
+    WeekendWork,
+    /// Billable entry tagged Ignore, which is removed from the tables
+    BillableIgnored,
+}
+
+/// Problem found in the entries of a user on a day
+#[derive(Debug, PartialEq, Clone)]
+pub struct Issue {
+    pub severity: Severity,
+    pub check: Check,
+    pub day: NaiveDate,
+    pub user: String,
+    pub message: String,
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.severity, self.day)?;
+        if !self.user.is_empty() {
+            write!(f, " {}", self.user)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+pub struct ValidateParam {
+    timezone: Tz,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    now: DateTime<Utc>,
+    max_day: TimeDelta,
+    calendar: Calendar,
+}
+
+impl ValidateParam {
+    pub fn build(args: &Args) -> Result<ValidateParam, Box<dyn Error>> {
+        Ok(ValidateParam {
+            timezone: args.timezone()?,
+            start: args.start(),
+            end: args.end(),
+            now: Utc::now(),
+            max_day: TimeDelta::seconds((args.max_hours * 3600.0).round() as i64),
+            calendar: Calendar::build(args)?,
+        })
+    }
+
+    pub fn calendar(&self) -> &Calendar {
+        &self.calendar
+    }
+
+    fn day(&self, time: DateTime<Utc>) -> NaiveDate {
+        time.with_timezone(&self.timezone).date_naive()
+    }
+}
+
+/// Check the entries of each user, once filtered and renamed, issues are sorted by day.
+/// Days without entries are only reported until yesterday, unless they are weekends,
+/// holidays or days of leave. Entries of the leave row only count as tracked days.
+pub fn validate(entries: &[Entry], p: &ValidateParam) -> Vec<Issue> {
+    let mut users: BTreeMap<&str, Vec<&Entry>> = BTreeMap::new();
+    for e in entries {
+        users.entry(&e.user).or_default().push(e);
+    }
+    for u in p.calendar.users() {
+        users.entry(u).or_default();
+    }
+    if users.is_empty() {
+        users.insert("", Vec::new());
+    }
+
+    let mut issues = Vec::new();
+    for (user, mut entries) in users {
+        let mut issue = |severity, check, day, message| {
+            issues.push(Issue {
+                severity,
+                check,
+                day,
+                user: user.to_string(),
+                message,
+            })
+        };
+        entries.sort_by_key(|e| (e.start, e.end));
+
+        let mut days: BTreeMap<NaiveDate, TimeDelta> = BTreeMap::new();
+        let mut last: Option<&Entry> = None;
+        for e in entries {
+            let day = p.day(e.start);
+            if e.project == LEAVE_ROW {
+                days.entry(day).or_default();
+                continue;
+            }
+            *days.entry(day).or_default() += e.duration();
+
+            if e.running {
+                let message = format!("entry {} is still running", e.id);
+                issue(Severity::Error, Check::RunningTimer, day, message);
+            }
+            if e.project.is_empty() {
+                let message = format!("entry {} has no project", e.id);
+                issue(Severity::Warning, Check::NoProject, day, message);
+            }
+            if let Some(l) = last.filter(|l| e.start < l.end) {
+                let message = format!("entries {} and {} overlap", l.id, e.id);
+                issue(Severity::Warning, Check::Overlap, day, message);
+            }
+            last = match last {
+                Some(l) if l.end >= e.end => Some(l),
+                _ => Some(e),
+            };
+        }
+
+        for (day, total) in &days {
+            if *total > p.max_day {
+                let message = format!(
+                    "{} hours tracked, more than {}",
+                    DecimalHours(*total),
+                    DecimalHours(p.max_day)
+                );
+                issue(Severity::Warning, Check::LongDay, *day, message);
+            }
+            if is_weekend(*day) && *total > TimeDelta::zero() {
+                let message = format!("{} hours tracked on a weekend", DecimalHours(*total));
+                issue(Severity::Warning, Check::WeekendWork, *day, message);
+            }
+        }
+
+        let first = p.day(p.start);
+        let last = p
+            .day(p.end - TimeDelta::nanoseconds(1))
+            .min(p.day(p.now).pred_opt().unwrap());
+        for day in first.iter_days().take_while(|d| *d <= last) {
+            if !days.contains_key(&day) && p.calendar.day_off(day, user).is_none() {
+                issue(
+                    Severity::Warning,
+                    Check::MissingDay,
+                    day,
+                    String::from("no time tracked"),
+                );
+            }
+        }
+    }
+
+    issues.sort_by_key(|i| i.day);
+    issues
+}
+
+/// Check the entries before they are filtered for billable entries tagged Ignore
+pub fn billable_ignored(entries: &[Entry], p: &ValidateParam) -> Vec<Issue> {
+    entries
+        .iter()
+        .filter(|e| e.billable && e.tags.iter().any(|t| t == "Ignore"))
+        .map(|e| Issue {
+            severity: Severity::Error,
+            check: Check::BillableIgnored,
+            day: p.day(e.start),
+            user: e.user.clone(),
+            message: format!("entry {} is billable but tagged Ignore", e.id),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Datelike, TimeZone};
+
+    use super::*;
+
+    fn param() -> ValidateParam {
+        let args = Args {
+            timezone: Some(String::from("UTC")),
+            start: Some(Utc.with_ymd_and_hms(2024, 10, 14, 0, 0, 0).unwrap()),
+            end: Some(Utc.with_ymd_and_hms(2024, 10, 21, 0, 0, 0).unwrap()),
+            ..Default::default()
+        };
+        ValidateParam {
+            now: Utc.with_ymd_and_hms(2024, 10, 25, 12, 0, 0).unwrap(),
+            ..ValidateParam::build(&args).unwrap()
+        }
+    }
+
+    fn entry(id: &str, d: u32, hour: u32, hours: i64) -> Entry {
+        let start = Utc.with_ymd_and_hms(2024, 10, d, hour, 0, 0).unwrap();
+        Entry {
+            id: id.to_string(),
+            project: String::from("Project1"),
+            start,
+            end: start + TimeDelta::hours(hours),
+            ..Default::default()
+        }
+    }
+
+    fn checks(issues: &[Issue]) -> Vec<(Check, u32)> {
+        issues.iter().map(|i| (i.check, i.day.day())).collect()
+    }
+
+    #[test]
+    fn valid_week() {
+        let entries: Vec<Entry> = (14..=18).map(|d| entry(&d.to_string(), d, 9, 8)).collect();
+        assert!(validate(&entries, &param()).is_empty());
+    }
+
+    #[test]
+    fn issues() {
+        let mut entries: Vec<Entry> = [14, 15, 17]
+            .iter()
+            .map(|d| entry(&d.to_string(), *d, 9, 8))
+            .collect();
+        entries.push(entry("overlap", 14, 16, 4));
+        entries.push(Entry {
+            project: String::new(),
+            ..entry("noproject", 18, 9, 2)
+        });
+        entries.push(entry("weekend", 19, 9, 2));
+        entries.push(Entry {
+            project: String::from(LEAVE_ROW),
+            ..entry("leave", 20, 0, 24)
+        });
+
+        let issues = validate(&entries, &param());
+        assert_eq!(
+            checks(&issues),
+            vec![
+                (Check::Overlap, 14),
+                (Check::LongDay, 14),
+                (Check::MissingDay, 16),
+                (Check::NoProject, 18),
+                (Check::WeekendWork, 19),
+            ]
+        );
+        assert_eq!(
+            issues[1].to_string(),
+            "warning 2024-10-14: 12.00 hours tracked, more than 10.00"
+        );
+    }
+
+    #[test]
+    fn ignored() {
+        let entries = vec![
+            Entry {
+                billable: true,
+                tags: vec![String::from("Ignore")],
+                ..entry("ignored", 18, 12, 2)
+            },
+            Entry {
+                tags: vec![String::from("Ignore")],
+                ..entry("unbillable", 18, 14, 2)
+            },
+        ];
+        let issues = billable_ignored(&entries, &param());
+        assert_eq!(checks(&issues), vec![(Check::BillableIgnored, 18)]);
+        assert_eq!(issues[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn running_and_users() {
+        let p = ValidateParam {
+            now: Utc.with_ymd_and_hms(2024, 10, 16, 10, 0, 0).unwrap(),
+            ..param()
+        };
+        let entries = vec![
+            Entry {
+                user: String::from("alice"),
+                ..entry("1", 14, 9, 8)
+            },
+            Entry {
+                user: String::from("alice"),
+                running: true,
+                ..entry("2", 16, 9, 2)
+            },
+            Entry {
+                user: String::from("bob"),
+                ..entry("3", 14, 12, 8)
+            },
+            Entry {
+                user: String::from("bob"),
+                ..entry("4", 15, 9, 8)
+            },
+            // Planned meeting, not running
+            Entry {
+                user: String::from("bob"),
+                ..entry("5", 16, 14, 1)
+            },
+        ];
+
+        let issues = validate(&entries, &p);
+        assert_eq!(
+            checks(&issues),
+            vec![(Check::MissingDay, 15), (Check::RunningTimer, 16)]
+        );
+        assert_eq!(
+            issues[0].to_string(),
+            "warning 2024-10-15 alice: no time tracked"
+        );
+        assert_eq!(
+            issues[1].to_string(),
+            "error 2024-10-16 alice: entry 2 is still running"
+        );
+    }
+}